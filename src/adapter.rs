@@ -112,6 +112,30 @@ pub fn main() {
         "applications/exit".to_string(),
         RequestTypes::ApplicationExitRequest,
     );
+    handlers.insert(
+        "applications/install".to_string(),
+        RequestTypes::ApplicationInstallRequest,
+    );
+    handlers.insert(
+        "applications/uninstall".to_string(),
+        RequestTypes::ApplicationUninstallRequest,
+    );
+    handlers.insert(
+        "applications/clear-data".to_string(),
+        RequestTypes::ApplicationClearDataRequest,
+    );
+    handlers.insert(
+        "applications/clients".to_string(),
+        RequestTypes::ApplicationClientsRequest,
+    );
+    handlers.insert(
+        "applications/focus".to_string(),
+        RequestTypes::ApplicationFocusRequest,
+    );
+    handlers.insert(
+        "applications/lifecycle-timeouts".to_string(),
+        RequestTypes::ApplicationLifecycleTimeoutsRequest,
+    );
     handlers.insert("device/info".to_string(), RequestTypes::DeviceInfoRequest);
     handlers.insert(
         "system/restart".to_string(),
@@ -141,6 +165,19 @@ pub fn main() {
         "input/long-key-press".to_string(),
         RequestTypes::InputLongKeyPressRequest,
     );
+    handlers.insert(
+        "input/key-sequence".to_string(),
+        RequestTypes::InputKeySequenceRequest,
+    );
+    handlers.insert("input/text".to_string(), RequestTypes::InputTextRequest);
+    handlers.insert(
+        "input/key-capture/start".to_string(),
+        RequestTypes::InputKeyCaptureStartRequest,
+    );
+    handlers.insert(
+        "input/key-capture/stop".to_string(),
+        RequestTypes::InputKeyCaptureStopRequest,
+    );
     handlers.insert("output/image".to_string(), RequestTypes::OutputImageRequest);
     handlers.insert(
         "health-check/get".to_string(),
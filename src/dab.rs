@@ -1,15 +1,19 @@
 use serde_json::Value;
+pub mod app_telemetry;
 pub mod device_telemetry;
 pub mod mqtt_client;
 pub mod structs;
 use crate::device::rdk as hw_specific;
 use mqtt_client::{MqttClient, MqttMessage};
+use crossbeam::channel::Sender;
+use std::sync::OnceLock;
 use std::time::{SystemTime, UNIX_EPOCH};
 use structs::{
     DabError, DabResponse, DiscoveryResponse, ErrorResponse, Messages, NotificationLevel,
     RequestTypes, SharedMap, TelemetryMessage,
 };
 
+use app_telemetry::AppTelemetry;
 use device_telemetry::DeviceTelemetry;
 
 fn call_function(json_str: String, request_type: RequestTypes) -> Result<String, DabError> {
@@ -44,6 +48,36 @@ fn call_function(json_str: String, request_type: RequestTypes) -> Result<String,
                 serde_json::from_str(&json_str).map_err(|e| DabError::Err400(e.to_string()))?;
             hw_specific::applications::exit::process(dab_request)
         }
+        RequestTypes::ApplicationInstallRequest => {
+            let dab_request: structs::InstallApplicationRequest =
+                serde_json::from_str(&json_str).map_err(|e| DabError::Err400(e.to_string()))?;
+            hw_specific::applications::install::process(dab_request)
+        }
+        RequestTypes::ApplicationUninstallRequest => {
+            let dab_request: structs::UninstallApplicationRequest =
+                serde_json::from_str(&json_str).map_err(|e| DabError::Err400(e.to_string()))?;
+            hw_specific::applications::uninstall::process(dab_request)
+        }
+        RequestTypes::ApplicationClearDataRequest => {
+            let dab_request: structs::ClearApplicationDataRequest =
+                serde_json::from_str(&json_str).map_err(|e| DabError::Err400(e.to_string()))?;
+            hw_specific::applications::clear_data::process(dab_request)
+        }
+        RequestTypes::ApplicationClientsRequest => {
+            let dab_request: structs::ApplicationClientsRequest =
+                serde_json::from_str(&json_str).map_err(|e| DabError::Err400(e.to_string()))?;
+            hw_specific::applications::clients::process(dab_request)
+        }
+        RequestTypes::ApplicationFocusRequest => {
+            let dab_request: structs::FocusApplicationRequest =
+                serde_json::from_str(&json_str).map_err(|e| DabError::Err400(e.to_string()))?;
+            hw_specific::applications::focus::process(dab_request)
+        }
+        RequestTypes::ApplicationLifecycleTimeoutsRequest => {
+            let dab_request: structs::LifecycleTimeoutsRequest =
+                serde_json::from_str(&json_str).map_err(|e| DabError::Err400(e.to_string()))?;
+            hw_specific::applications::lifecycle_timeouts::process(dab_request)
+        }
         RequestTypes::DeviceInfoRequest => {
             let dab_request: structs::DeviceInfoRequest =
                 serde_json::from_str(&json_str).map_err(|e| DabError::Err400(e.to_string()))?;
@@ -84,6 +118,26 @@ fn call_function(json_str: String, request_type: RequestTypes) -> Result<String,
                 serde_json::from_str(&json_str).map_err(|e| DabError::Err400(e.to_string()))?;
             hw_specific::input::long_key_press::process(dab_request)
         }
+        RequestTypes::InputKeySequenceRequest => {
+            let dab_request: structs::KeySequenceRequest =
+                serde_json::from_str(&json_str).map_err(|e| DabError::Err400(e.to_string()))?;
+            hw_specific::input::key_sequence::process(dab_request)
+        }
+        RequestTypes::InputTextRequest => {
+            let dab_request: structs::InputTextRequest =
+                serde_json::from_str(&json_str).map_err(|e| DabError::Err400(e.to_string()))?;
+            hw_specific::input::text::process(dab_request)
+        }
+        RequestTypes::InputKeyCaptureStartRequest => {
+            let dab_request: structs::KeyCaptureStartRequest =
+                serde_json::from_str(&json_str).map_err(|e| DabError::Err400(e.to_string()))?;
+            hw_specific::input::key_capture::start(dab_request)
+        }
+        RequestTypes::InputKeyCaptureStopRequest => {
+            let dab_request: structs::KeyCaptureStopRequest =
+                serde_json::from_str(&json_str).map_err(|e| DabError::Err400(e.to_string()))?;
+            hw_specific::input::key_capture::stop(dab_request)
+        }
         RequestTypes::OutputImageRequest => {
             let dab_request: structs::CaptureScreenshotRequest =
                 serde_json::from_str(&json_str).map_err(|e| DabError::Err400(e.to_string()))?;
@@ -122,15 +176,57 @@ fn call_function(json_str: String, request_type: RequestTypes) -> Result<String,
     }
 }
 
+struct Notifier {
+    publisher: Sender<MqttMessage>,
+    device_id: String,
+    ip: String,
+}
+
+static NOTIFIER: OnceLock<Notifier> = OnceLock::new();
+
+// Publishes a notification to the dab/<device-id>/messages topic. Notifications sent before the
+// adapter is connected to the broker are dropped.
+pub fn notify(level: NotificationLevel, message: &str) {
+    let Some(notifier) = NOTIFIER.get() else {
+        return;
+    };
+
+    let payload = serde_json::to_string(&Messages {
+        timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+        level,
+        ip: notifier.ip.clone(),
+        message: message.to_string(),
+    })
+    .unwrap();
+
+    publish("messages", payload);
+}
+
+// Publishes the payload to the dab/<device-id>/<topic> topic. Like notifications, it is dropped
+// before the adapter is connected to the broker.
+pub fn publish(topic: &str, payload: String) {
+    let Some(notifier) = NOTIFIER.get() else {
+        return;
+    };
+
+    let msg_tx = MqttMessage {
+        function_topic: format!("dab/{}/{}", notifier.device_id, topic),
+        response_topic: "".to_string(),
+        correlation_data: vec![0],
+        payload,
+    };
+    let _ = notifier.publisher.send(msg_tx);
+}
+
 pub fn run(mqtt_server: String, mqtt_port: u16, mut function_map: SharedMap) {
     // Get the device ID
     let device_id = match hw_specific::interface::get_device_id() {
         Ok(id) => id,
-        Err(_) => {
-            println!("RDK: Error getting device ID");
+        Err(e) => {
+            println!("RDK: Error getting device ID: {:?}", e);
             // Without a valid Device ID; DAB functionality cannot progress.
-            // Exit now so that systemd can restart it again.
-            std::process::exit(0x00);
+            // Exit with a failure status so that systemd restarts it again.
+            std::process::exit(0x01);
         }
     };
     println!("DAB Device ID: {}", device_id);
@@ -142,36 +238,24 @@ pub fn run(mqtt_server: String, mqtt_port: u16, mut function_map: SharedMap) {
     mqtt_client.subscribe("dab/".to_string() + &device_id + "/#");
     mqtt_client.subscribe("dab/discovery".to_string());
 
-    // Broadcast a message to dab/<device-id>/messages topic:
-
-    let now = SystemTime::now();
-    let unix_time = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
-
     let ip_address = hw_specific::interface::get_ip_address();
 
-    let payload = serde_json::to_string(&Messages {
-        timestamp: unix_time,
-        level: NotificationLevel::info,
+    let _ = NOTIFIER.set(Notifier {
+        publisher: mqtt_client.publisher(),
+        device_id: device_id.clone(),
         ip: ip_address.clone(),
-        message: "DAB started successfully".to_string(),
-    })
-    .unwrap();
+    });
 
-    let mut zero_vector: Vec<u8> = Vec::new();
-    zero_vector.push(0);
-
-    let msg_tx = MqttMessage {
-        function_topic: "dab/".to_string() + &device_id.clone() + "/messages",
-        response_topic: "".to_string(),
-        correlation_data: zero_vector,
-        payload: payload.clone(),
-    };
-    mqtt_client.publish(msg_tx);
+    // Broadcast a message to dab/<device-id>/messages topic:
+    notify(NotificationLevel::info, "DAB started successfully");
 
     // Start the device telemetry thread
     let mqtt_client_telemetry = mqtt_client.clone();
     let mut device_telemetry = DeviceTelemetry::new(mqtt_client_telemetry, device_id.clone());
 
+    // App telemetry threads are started on request, one per app
+    let mut app_telemetry = AppTelemetry::new(mqtt_client.clone(), device_id.clone());
+
     // Infinite loop
     loop {
         // Check for messages
@@ -211,7 +295,8 @@ pub fn run(mqtt_server: String, mqtt_port: u16, mut function_map: SharedMap) {
                         }
                         // If we can't get the proper handler, then this is a telemetry operation or is not implemented
                         _ => {
-                            // If the operation is device-telemetry/start, then start the device telemetry thread
+                            // If the operation is device-telemetry/start, then start the device telemetry thread;
+                            // app-telemetry/start starts a telemetry thread for the app
                             if &operation == "device-telemetry/start" {
                                 let dab_request: Result<
                                     structs::StartDeviceTelemetryRequest,
@@ -232,6 +317,26 @@ pub fn run(mqtt_server: String, mqtt_port: u16, mut function_map: SharedMap) {
                                     Ok(r) => device_telemetry.device_telemetry_stop_process(r),
                                     Err(e) => Err(e),
                                 }
+                            } else if &operation == "app-telemetry/start" {
+                                let dab_request: Result<
+                                    structs::StartApplicationTelemetryRequest,
+                                    DabError,
+                                > = serde_json::from_str(&payload.clone())
+                                    .map_err(|e| DabError::Err400(e.to_string()));
+                                match dab_request {
+                                    Ok(r) => app_telemetry.app_telemetry_start_process(r),
+                                    Err(e) => Err(e),
+                                }
+                            } else if &operation == "app-telemetry/stop" {
+                                let dab_request: Result<
+                                    structs::StopApplicationTelemetryRequest,
+                                    DabError,
+                                > = serde_json::from_str(&payload.clone())
+                                    .map_err(|e| DabError::Err400(e.to_string()));
+                                match dab_request {
+                                    Ok(r) => app_telemetry.app_telemetry_stop_process(r),
+                                    Err(e) => Err(e),
+                                }
                             } else {
                                 println!("ERROR: {}", operation);
                                 Err(DabError::Err501(operation + " operator not implemented"))
@@ -0,0 +1,203 @@
+use crate::dab::structs::DabError;
+use crate::dab::structs::StartApplicationTelemetryRequest;
+use crate::dab::structs::StartApplicationTelemetryResponse;
+use crate::dab::structs::StopApplicationTelemetryRequest;
+use crate::dab::structs::StopApplicationTelemetryResponse;
+use crate::dab::{mqtt_client::MqttMessage, MqttClient, TelemetryMessage};
+use crate::hw_specific::interface::{app_cpu_available, forget_app_cpu, get_app_cpu};
+use crate::hw_specific::interface::{app_memory_available, get_app_memory};
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, Condvar},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+// Shortest accepted period between two samples.
+const MIN_TELEMETRY_PERIOD_MS: u64 = 100;
+
+type MetricReader = fn(&str) -> Result<u32, DabError>;
+
+struct ThreadContext {
+    mutex: Mutex<bool>,
+    cond: Condvar,
+}
+
+struct TelemetryThread {
+    handle: thread::JoinHandle<()>,
+    context: Arc<ThreadContext>,
+}
+
+// Publishes the metrics of each tracked app from its own thread.
+pub struct AppTelemetry {
+    threads: HashMap<String, TelemetryThread>,
+    mqtt_client: MqttClient,
+    device_id: String,
+}
+
+impl AppTelemetry {
+    pub fn new(mqtt_client: MqttClient, device_id: String) -> AppTelemetry {
+        AppTelemetry {
+            threads: HashMap::new(),
+            mqtt_client,
+            device_id,
+        }
+    }
+
+    pub fn start(&mut self, app_id: &str, period: u64) {
+        // If it is already running for this app, stop the instance before creating a new one
+        self.stop(app_id);
+
+        let context = Arc::new(ThreadContext {
+            mutex: Mutex::new(true),
+            cond: Condvar::new(),
+        });
+        let thread_context = context.clone();
+
+        // Metrics that can't be read on this setup are left out instead of failing every sample.
+        let metrics: [(&'static str, bool, MetricReader); 2] = [
+            ("memory", app_memory_available(), get_app_memory),
+            ("cpu", app_cpu_available(), get_app_cpu),
+        ];
+        let mut readers = vec![];
+        for (metric_name, available, read_metric) in metrics {
+            if available {
+                readers.push((metric_name, read_metric));
+            } else {
+                println!("app-telemetry: {} of {} can't be read on this setup and is left out.", metric_name, app_id);
+            }
+        }
+
+        let topic = format!("dab/{}/app-telemetry/metrics/{}", self.device_id, app_id);
+        let app_id = app_id.to_string();
+        let mqtt_client = self.mqtt_client.clone();
+
+        let handle = thread::spawn({
+            let app_id = app_id.clone();
+            move || {
+                let ThreadContext {mutex, cond} = &*thread_context;
+                // Metrics whose last read failed; a failure is logged when it starts, not on
+                // every sample.
+                let mut failing = HashSet::new();
+
+                loop {
+                    let zero_vector = vec![0];
+
+                    for (metric_name, read_metric) in &readers {
+                        // A metric that can't be read is skipped rather than published as 0.
+                        let value = match read_metric(&app_id) {
+                            Ok(val) => {
+                                failing.remove(metric_name);
+                                val
+                            }
+                            Err(err) => {
+                                if failing.insert(metric_name) {
+                                    eprintln!("app-telemetry: unable to read {} of {}: {:?}", metric_name, app_id, err);
+                                }
+                                continue;
+                            }
+                        };
+
+                        let payload = Self::get_telemetry_payload(metric_name, value).unwrap();
+
+                        let msg_tx = MqttMessage {
+                            function_topic: topic.clone(),
+                            response_topic: "".to_string(),
+                            correlation_data: zero_vector.clone(),
+                            payload,
+                        };
+
+                        mqtt_client.publish(msg_tx);
+                    }
+
+                    if !cond.wait_timeout_while(
+                        mutex.lock().unwrap(),
+                        Duration::from_millis(period),
+                        |&mut enabled| enabled
+                    ).unwrap().1.timed_out() {
+                        break;
+                    }
+                }
+
+                forget_app_cpu(&app_id);
+            }
+        });
+
+        self.threads.insert(app_id, TelemetryThread { handle, context });
+    }
+
+    // Returns false when telemetry wasn't running for the app.
+    pub fn stop(&mut self, app_id: &str) -> bool {
+        match self.threads.remove(app_id) {
+            Some(telemetry) => {
+                *telemetry.context.mutex.lock().unwrap() = false;
+                telemetry.context.cond.notify_one();
+                telemetry.handle.join().unwrap();
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[allow(non_snake_case)]
+    pub fn app_telemetry_start_process(
+        &mut self,
+        _dab_request: StartApplicationTelemetryRequest,
+    ) -> Result<String, DabError> {
+        let mut ResponseOperator = StartApplicationTelemetryResponse::default();
+
+        if _dab_request.appId.is_empty() {
+            return Err(DabError::Err400(
+                "request missing 'appId' parameter".to_string(),
+            ));
+        }
+
+        if _dab_request.duration < MIN_TELEMETRY_PERIOD_MS {
+            return Err(DabError::Err400(format!(
+                "'duration' must be at least {}ms",
+                MIN_TELEMETRY_PERIOD_MS
+            )));
+        }
+
+        self.start(&_dab_request.appId, _dab_request.duration);
+
+        ResponseOperator.duration = _dab_request.duration;
+
+        Ok(serde_json::to_string(&ResponseOperator).unwrap())
+    }
+
+    #[allow(non_snake_case)]
+    pub fn app_telemetry_stop_process(
+        &mut self,
+        _dab_request: StopApplicationTelemetryRequest,
+    ) -> Result<String, DabError> {
+        let ResponseOperator = StopApplicationTelemetryResponse::default();
+
+        if _dab_request.appId.is_empty() {
+            return Err(DabError::Err400(
+                "request missing 'appId' parameter".to_string(),
+            ));
+        }
+
+        if !self.stop(&_dab_request.appId) {
+            return Err(DabError::Err400(
+                format!("app-telemetry is not started for {}", _dab_request.appId),
+            ));
+        }
+
+        Ok(serde_json::to_string(&ResponseOperator).unwrap())
+    }
+
+    fn get_telemetry_payload(metric: &str, value: u32) -> Result<String, serde_json::Error> {
+        let message = TelemetryMessage {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            metric: metric.to_string(),
+            value,
+        };
+        serde_json::to_string(&message)
+    }
+}
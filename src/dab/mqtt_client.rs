@@ -87,6 +87,10 @@ impl MqttClient {
             println!("Error subscribing to topic: {:?}", e);
         }
     }
+    // Returns a handle for publishing from other threads without sharing the client.
+    pub fn publisher(&self) -> Sender<MqttMessage> {
+        self.ipc_channel.0.clone()
+    }
     pub fn publish(&self, msg_tx: MqttMessage) {
         self.ipc_channel.0.send(msg_tx).unwrap();
     }
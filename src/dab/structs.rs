@@ -18,6 +18,12 @@ pub enum RequestTypes {
     ApplicationLaunchWithContentRequest,
     ApplicationGetStateRequest,
     ApplicationExitRequest,
+    ApplicationInstallRequest,
+    ApplicationUninstallRequest,
+    ApplicationClearDataRequest,
+    ApplicationClientsRequest,
+    ApplicationFocusRequest,
+    ApplicationLifecycleTimeoutsRequest,
     DeviceInfoRequest,
     SystemRestartRequest,
     SystemSettingsListRequest,
@@ -26,6 +32,10 @@ pub enum RequestTypes {
     InputKeyListRequest,
     InputKeyPressRequest,
     InputLongKeyPressRequest,
+    InputKeySequenceRequest,
+    InputTextRequest,
+    InputKeyCaptureStartRequest,
+    InputKeyCaptureStopRequest,
     OutputImageRequest,
     HealthCheckGetRequest,
     VoiceListRequest,
@@ -170,33 +180,25 @@ pub struct Version {
     pub versions: Vec<String>,
 }
 
-#[allow(dead_code)]
 #[allow(non_snake_case)]
 #[derive(Default, Serialize, Deserialize)]
-#[allow(dead_code)]
 pub struct StopApplicationTelemetryRequest {
     pub appId: String,
 }
 
-#[allow(dead_code)]
 #[allow(non_snake_case)]
 #[derive(Default, Serialize, Deserialize)]
-#[allow(dead_code)]
 pub struct StopApplicationTelemetryResponse {}
 
-#[allow(dead_code)]
 #[allow(non_snake_case)]
 #[derive(Default, Serialize, Deserialize)]
-#[allow(dead_code)]
 pub struct StartApplicationTelemetryRequest {
     pub appId: String,
     pub duration: u64,
 }
 
-#[allow(dead_code)]
 #[allow(non_snake_case)]
 #[derive(Default, Serialize, Deserialize)]
-#[allow(dead_code)]
 pub struct StartApplicationTelemetryResponse {
     pub duration: u64,
 }
@@ -452,9 +454,119 @@ pub struct LaunchApplicationWithContentRequest {
 
 #[allow(dead_code)]
 #[allow(non_snake_case)]
+#[skip_serializing_none]
 #[derive(Default, Serialize, Deserialize)]
 #[allow(dead_code)]
-pub struct LaunchApplicationWithContentResponse {}
+pub struct LaunchApplicationWithContentResponse {
+    pub timing: Option<LaunchTiming>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct InstallApplicationRequest {
+    pub url: String,
+    pub appId: Option<String>,
+    pub version: Option<String>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct InstallApplicationResponse {
+    pub appId: String,
+}
+
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct UninstallApplicationRequest {
+    pub appId: String,
+}
+
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct UninstallApplicationResponse {}
+
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct ClearApplicationDataRequest {
+    pub appId: String,
+}
+
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct ClearApplicationDataResponse {
+    pub cleared: Vec<String>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct ApplicationClientsRequest {}
+
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct ApplicationClientsResponse {
+    pub clients: Vec<ApplicationClient>,
+}
+
+#[allow(non_snake_case)]
+#[skip_serializing_none]
+#[derive(Default, Serialize, Deserialize)]
+pub struct ApplicationClient {
+    pub client: String,
+    // Position in the RDKShell Z order, 0 being the front most; none for clients without display.
+    pub zOrder: Option<usize>,
+    pub visible: Option<bool>,
+    pub focused: bool,
+    // State reported by RDKShell, e.g. "resumed" or "suspended".
+    pub state: Option<String>,
+    pub memoryKb: Option<u32>,
+    // Platform clients such as subtitles, not applications.
+    pub system: bool,
+}
+
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct FocusApplicationRequest {
+    pub appId: String,
+    pub moveToFront: Option<bool>,
+    pub setFocus: Option<bool>,
+}
+
+#[allow(non_snake_case)]
+#[skip_serializing_none]
+#[derive(Default, Serialize, Deserialize)]
+pub struct FocusApplicationResponse {
+    pub focusedClient: Option<String>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct LifecycleTimeoutsRequest {
+    pub appId: Option<String>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct LifecycleTimeoutsResponse {
+    // Timeouts of apps without values of their own.
+    pub defaults: Vec<LifecycleTimeout>,
+    pub apps: Vec<AppLifecycleTimeouts>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct AppLifecycleTimeouts {
+    pub appId: String,
+    pub timeouts: Vec<LifecycleTimeout>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct LifecycleTimeout {
+    pub name: String,
+    pub valueMs: u64,
+    // "platform", "catalog", "platform-default" or "built-in".
+    pub source: String,
+}
 
 #[allow(non_snake_case)]
 #[derive(Default, Serialize, Deserialize)]
@@ -465,18 +577,40 @@ pub struct LaunchApplicationRequest {
 
 #[allow(dead_code)]
 #[allow(non_snake_case)]
+#[skip_serializing_none]
 #[derive(Default, Serialize, Deserialize)]
 #[allow(dead_code)]
-pub struct LaunchApplicationResponse {}
+pub struct LaunchApplicationResponse {
+    pub timing: Option<LaunchTiming>,
+}
+
+// Milliseconds from receiving the launch request.
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize, Clone)]
+pub struct LaunchTiming {
+    // "cold" or "resume".
+    pub launchType: String,
+    pub requestAcceptedMs: u64,
+    pub foregroundMs: u64,
+    pub focusedMs: u64,
+}
 
 #[allow(non_snake_case)]
 #[derive(Default, Serialize, Deserialize)]
 pub struct ApplicationListRequest {}
 
 #[allow(non_snake_case)]
+#[skip_serializing_none]
 #[derive(Default, Serialize, Deserialize)]
 pub struct Application {
     pub appId: String,
+    #[cfg(feature = "2_1")]
+    pub friendlyName: Option<String>,
+    #[cfg(feature = "2_1")]
+    pub version: Option<String>,
+    pub iconUrl: Option<String>,
+    pub installSource: Option<String>,
+    pub running: Option<bool>,
 }
 
 #[allow(non_snake_case)]
@@ -493,9 +627,22 @@ pub struct ExitApplicationRequest {
 }
 
 #[allow(non_snake_case)]
+#[skip_serializing_none]
 #[derive(Default, Serialize, Deserialize)]
 pub struct ExitApplicationResponse {
     pub state: String,
+    pub timing: Option<ExitTiming>,
+}
+
+// Milliseconds from receiving the exit request.
+#[allow(non_snake_case)]
+#[skip_serializing_none]
+#[derive(Default, Serialize, Deserialize, Clone)]
+pub struct ExitTiming {
+    // "background" or "destroy".
+    pub exitType: String,
+    pub requestAcceptedMs: Option<u64>,
+    pub exitedMs: Option<u64>,
 }
 
 #[allow(non_snake_case)]
@@ -512,6 +659,8 @@ pub struct KeyList {
 #[derive(Default, Serialize, Deserialize)]
 pub struct KeyPressRequest {
     pub keyCode: String,
+    // App to send the key to, even when another client has focus.
+    pub appId: Option<String>,
 }
 
 #[allow(non_snake_case)]
@@ -523,6 +672,8 @@ pub struct KeyPressResponse {}
 pub struct LongKeyPressRequest {
     pub keyCode: String,
     pub durationMs: u32,
+    // App to send the key to, even when another client has focus.
+    pub appId: Option<String>,
 }
 
 #[allow(non_snake_case)]
@@ -531,62 +682,108 @@ pub struct LongKeyPressResponse {}
 
 #[allow(non_snake_case)]
 #[derive(Default, Serialize, Deserialize)]
-pub struct CaptureScreenshotRequest {}
+pub struct KeySequenceRequest {
+    pub keys: Vec<KeySequenceStep>,
+}
 
 #[allow(non_snake_case)]
 #[derive(Default, Serialize, Deserialize)]
-pub struct CaptureScreenshotResponse {
-    pub outputImage: String,
+pub struct KeySequenceStep {
+    pub keyCode: String,
+    // Holds the key for this long instead of pressing it.
+    pub durationMs: Option<u32>,
+    // Pause after the step.
+    pub delayMs: Option<u32>,
+    // RDKShell modifiers held with the key: "ctrl", "alt" or "shift".
+    pub modifiers: Option<Vec<String>>,
 }
 
-// Implement device-telemetry
 #[allow(non_snake_case)]
 #[derive(Default, Serialize, Deserialize)]
-pub struct StopDeviceTelemetryRequest {}
+pub struct KeySequenceResponse {
+    pub completedSteps: usize,
+}
 
 #[allow(non_snake_case)]
 #[derive(Default, Serialize, Deserialize)]
-pub struct StopDeviceTelemetryResponse {}
+pub struct InputTextRequest {
+    pub text: String,
+    // App to type into; the focused app by default.
+    pub appId: Option<String>,
+}
 
 #[allow(non_snake_case)]
 #[derive(Default, Serialize, Deserialize)]
-pub struct StartDeviceTelemetryRequest {
-    pub duration: u64,
+pub struct InputTextResponse {
+    // "keys" or "deeplink".
+    pub method: String,
 }
 
 #[allow(non_snake_case)]
 #[derive(Default, Serialize, Deserialize)]
-pub struct StartDeviceTelemetryResponse {
-    pub duration: u64,
+pub struct KeyCaptureStartRequest {}
+
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct KeyCaptureStartResponse {
+    // RDKShell clients whose keys are captured.
+    pub clients: Vec<String>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct KeyCaptureStopRequest {}
+
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct KeyCaptureStopResponse {}
+
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct CaptureScreenshotRequest {
+    // "png" (default), "jpeg" or "webp".
+    pub format: Option<String>,
+    // JPEG quality, 1 to 100.
+    pub quality: Option<u8>,
+    // Target size; with only one of them the aspect ratio is kept.
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    // Region of the full resolution screen, applied before scaling.
+    pub crop: Option<ScreenshotCrop>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct ScreenshotCrop {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[allow(non_snake_case)]
+#[derive(Default, Serialize, Deserialize)]
+pub struct CaptureScreenshotResponse {
+    pub outputImage: String,
 }
 
 // Implement device-telemetry
-#[allow(dead_code)]
 #[allow(non_snake_case)]
 #[derive(Default, Serialize, Deserialize)]
-#[allow(dead_code)]
-pub struct StopAppTelemetryRequest {}
+pub struct StopDeviceTelemetryRequest {}
 
-#[allow(dead_code)]
 #[allow(non_snake_case)]
 #[derive(Default, Serialize, Deserialize)]
-#[allow(dead_code)]
-pub struct StopAppTelemetryResponse {}
+pub struct StopDeviceTelemetryResponse {}
 
-#[allow(dead_code)]
 #[allow(non_snake_case)]
 #[derive(Default, Serialize, Deserialize)]
-#[allow(dead_code)]
-pub struct StartAppTelemetryRequest {
-    pub app_id: String,
+pub struct StartDeviceTelemetryRequest {
     pub duration: u64,
 }
 
-#[allow(dead_code)]
 #[allow(non_snake_case)]
 #[derive(Default, Serialize, Deserialize)]
-#[allow(dead_code)]
-pub struct StartAppTelemetryResponse {
-    pub app_id: String,
+pub struct StartDeviceTelemetryResponse {
     pub duration: u64,
 }
@@ -3,6 +3,8 @@ pub mod device;
 pub mod health_check;
 pub mod input;
 pub mod interface;
+#[cfg(test)]
+pub mod mock_thunder;
 pub mod operations;
 pub mod output;
 pub mod system;
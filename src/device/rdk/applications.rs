@@ -1,5 +1,17 @@
+pub mod app_manager;
+pub mod catalog;
+pub mod clear_data;
+pub mod clients;
+pub mod crash_monitor;
 pub mod exit;
+pub mod focus;
 pub mod get_state;
+pub mod install;
 pub mod launch;
+pub mod launch_policy;
 pub mod launch_with_content;
+pub mod lifecycle_timeouts;
 pub mod list;
+pub mod packages;
+pub mod state_cache;
+pub mod uninstall;
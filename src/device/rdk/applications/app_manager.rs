@@ -0,0 +1,145 @@
+// Application lifecycle for RDK-E devices, where the AppManager and LifecycleManager plugins
+// replace RDKShell. See get_app_lifecycle_backend().
+
+use crate::dab::structs::DabError;
+use crate::device::rdk::applications::get_state::AppState;
+use crate::device::rdk::interface::rdk_request;
+use crate::device::rdk::interface::rdk_request_with_params;
+use serde::Serialize;
+use serde_json::Value;
+
+// AppManager returns app lists either as a JSON array or as a JSON encoded string, depending on
+// the release; accept both, bare or wrapped in an "apps" member.
+fn parse_app_list(result: &Value) -> Vec<Value> {
+    let apps = match result.get("apps") {
+        Some(apps) => apps,
+        None => result,
+    };
+
+    match apps {
+        Value::Array(list) => list.clone(),
+        Value::String(encoded) => match serde_json::from_str::<Value>(encoded) {
+            Ok(Value::Array(list)) => list,
+            _ => vec![],
+        },
+        _ => vec![],
+    }
+}
+
+// Maps a LifecycleManager state to the adapter application state. The state is reported as a
+// name ("APP_STATE_ACTIVE", "ACTIVE") or as the LifecycleManager enum value.
+fn to_app_state(state: &Value) -> AppState {
+    let name = match state {
+        Value::Number(n) => match n.as_u64() {
+            Some(1) => "LOADING",
+            Some(2) => "INITIALIZING",
+            Some(3) => "PAUSED",
+            Some(4) => "ACTIVE",
+            Some(5) => "SUSPENDED",
+            Some(6) => "HIBERNATED",
+            _ => "UNLOADED",
+        }
+        .to_string(),
+        Value::String(s) => s.to_uppercase().trim_start_matches("APP_STATE_").to_string(),
+        _ => "UNLOADED".to_string(),
+    };
+
+    match name.as_str() {
+        "ACTIVE" => AppState::Visible,
+        "LOADING" | "INITIALIZING" | "PAUSED" | "RUNNING" => AppState::Invisible,
+        "SUSPENDED" => AppState::Suspended,
+        "HIBERNATED" => AppState::Hibernated,
+        _ => AppState::Stopped,
+    }
+}
+
+fn get_loaded_apps() -> Result<Vec<Value>, DabError> {
+    let rdkresponse: Value = rdk_request("org.rdk.AppManager.getLoadedApps")?;
+    Ok(parse_app_list(&rdkresponse["result"]))
+}
+
+pub fn get_loaded_app_ids() -> Result<Vec<String>, DabError> {
+    Ok(get_loaded_apps()?
+        .iter()
+        .filter_map(|app| app["appId"].as_str().map(|id| id.to_string()))
+        .collect())
+}
+
+pub fn get_app_state(app_id: &str) -> Result<AppState, DabError> {
+    for app in get_loaded_apps()? {
+        if app["appId"].as_str().unwrap_or("").eq_ignore_ascii_case(app_id) {
+            let state = ["currentLifecycleState", "lifecycleState", "state"]
+                .iter()
+                .map(|key| &app[*key])
+                .find(|value| !value.is_null())
+                .cloned()
+                .unwrap_or(Value::Null);
+            return Ok(to_app_state(&state));
+        }
+    }
+
+    Ok(AppState::Stopped)
+}
+
+pub fn get_installed_apps() -> Result<Vec<String>, DabError> {
+    let rdkresponse: Value = rdk_request("org.rdk.AppManager.getInstalledApps")?;
+
+    Ok(parse_app_list(&rdkresponse["result"])
+        .iter()
+        .filter_map(|app| app["appId"].as_str().map(|id| id.to_string()))
+        .collect())
+}
+
+// Launches or resumes the app; AppManager decides between cold launch and resume.
+pub fn launch_app(app_id: &str, launch_args: &str) -> Result<(), DabError> {
+    #[allow(non_snake_case)]
+    #[derive(Serialize)]
+    struct Param {
+        appId: String,
+        intent: String,
+        launchArgs: String,
+    }
+
+    let _rdkresponse: Value = rdk_request_with_params(
+        "org.rdk.AppManager.launchApp",
+        Param {
+            appId: app_id.to_string(),
+            intent: String::new(),
+            launchArgs: launch_args.to_string(),
+        },
+    )?;
+    Ok(())
+}
+
+// Moves the app out of the foreground, keeping it loaded.
+pub fn close_app(app_id: &str) -> Result<(), DabError> {
+    #[allow(non_snake_case)]
+    #[derive(Serialize)]
+    struct Param {
+        appId: String,
+    }
+
+    let _rdkresponse: Value = rdk_request_with_params(
+        "org.rdk.AppManager.closeApp",
+        Param {
+            appId: app_id.to_string(),
+        },
+    )?;
+    Ok(())
+}
+
+pub fn terminate_app(app_id: &str) -> Result<(), DabError> {
+    #[allow(non_snake_case)]
+    #[derive(Serialize)]
+    struct Param {
+        appId: String,
+    }
+
+    let _rdkresponse: Value = rdk_request_with_params(
+        "org.rdk.AppManager.terminateApp",
+        Param {
+            appId: app_id.to_string(),
+        },
+    )?;
+    Ok(())
+}
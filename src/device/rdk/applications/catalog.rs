@@ -0,0 +1,495 @@
+use crate::dab::structs::DabError;
+use crate::device::rdk::interface::read_platform_config_json;
+use crate::device::rdk::system::settings::get::get_rdk_language;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Deserializer};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use urlencoding::decode;
+
+// The application catalog describes how each DAB appId is run on the platform. A built-in catalog
+// is used unless /etc/dab/apps.json is present; in both cases, entries may be added or replaced
+// via the /opt/dab_platform_apps.json file. Apps missing from the catalog are launched as
+// LightningApp using the appId as callsign.
+//
+// The catalog files must conform to the following format:
+/*
+    {
+        "YouTube": {
+            "aliases": ["Cobalt"],
+            "callsign": "YouTube",
+            "friendly_name": "YouTube",
+            "rdkshell_type": "Cobalt",
+            "available_type": "YouTube",
+            "url": "https://www.youtube.com/tv?{parameters}",
+            "configuration": { "url": "{url}", "language": "{language}" },
+            "content_template": "v={contentId}",
+            "parameter_encoding": { "decode": true, "separator": "&" },
+            "deeplink": { "type": "jsonrpc", "method": "{callsign}.1.deeplink", "params": "{url}" },
+            "text_input": { "type": "deeplink", "parameter": "search_query={text}" },
+            "lifecycle_timeouts": {
+                "cold_launch_timeout_ms": 6000,
+                "resume_launch_timeout_ms": 3000,
+                "exit_to_destroy_timeout_ms": 2500,
+                "exit_to_background_timeout_ms": 2000
+            }
+        },
+        "Netflix": {
+            "rdkshell_type": "Netflix",
+            "configuration": { "querystring": "{parameters}" },
+            "content_template": "m={contentId}",
+            "deeplink": [
+                { "type": "jsonrpc", "method": "{callsign}.1.systemcommand", "params": { "command": "{parameters}" } },
+                { "type": "jsonrpc", "method": "{callsign}.1.deeplink", "params": "{parameters}" },
+                { "type": "relaunch" }
+            ]
+        },
+        "MyLightningApp": {
+            "friendly_name": "My Lightning App",
+            "icon_url": "https://apps.example.com/my-app/icon.png",
+            "uri": "https://apps.example.com/my-app/index.html?{parameters}",
+            "background_exit": "hibernate",
+            "relaunch_on_crash": true,
+            "data_namespaces": ["{callsign}"],
+            "data_dirs": ["/opt/persistent/rdkservices/{callsign}"]
+        }
+    }
+*/
+// Templates may use the {appId}, {callsign}, {parameters}, {url} and {language} placeholders.
+// "friendly_name" and "icon_url" are reported by applications/list. "callsign" defaults to the
+// appId of the request, "rdkshell_type" to LightningApp and "available_type" (the
+// RDKShell.getAvailableTypes entry that makes the app listed) to "rdkshell_type".
+// "content_template" turns the contentId of launch-with-content into a launch parameter,
+// "contentId={contentId}" by default. "deeplink" is a strategy or a list of strategies, either a
+// "jsonrpc" call or "relaunch" (destroy, then cold launch with the parameters).
+// "background_exit" is what applications/exit does with background: true, "suspend" (default),
+// "hibernate" or "destroy". applications/clear-data deletes the "data_namespaces" of
+// PersistentStore (the callsign by default) and, when running on the device, empties the
+// "data_dirs". With "relaunch_on_crash", an app that crashed while running
+// is launched again. "text_input" is how input/text types into the app: "keys" (default) presses
+// the keys of each character, "deeplink" passes the URL encoded text to the running app as the
+// launch parameter "parameter", e.g. "search_query={text}".
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ParameterEncoding {
+    // URL decode each DAB launch parameter before passing it to the app.
+    #[serde(default)]
+    pub decode: bool,
+    #[serde(default = "default_parameter_separator")]
+    pub separator: String,
+}
+
+fn default_parameter_separator() -> String {
+    "&".to_string()
+}
+
+impl Default for ParameterEncoding {
+    fn default() -> Self {
+        ParameterEncoding {
+            decode: false,
+            separator: default_parameter_separator(),
+        }
+    }
+}
+
+// Way of passing launch parameters to an app that is already running. An app lists one or more
+// strategies, tried in order until one succeeds.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DeeplinkStrategy {
+    // JSON-RPC call to the app plugin.
+    Jsonrpc { method: String, params: Value },
+    // Destroy the app and cold launch it with the parameters.
+    Relaunch,
+}
+
+fn deserialize_deeplink<'de, D>(deserializer: D) -> Result<Vec<DeeplinkStrategy>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(DeeplinkStrategy),
+        Many(Vec<DeeplinkStrategy>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(strategy) => vec![strategy],
+        OneOrMany::Many(strategies) => strategies,
+    })
+}
+
+// What applications/exit does with the app when asked to move it to the background.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BackgroundExitPolicy {
+    #[default]
+    Suspend,
+    Hibernate,
+    Destroy,
+}
+
+// How input/text types into the app.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TextInputStrategy {
+    // Key presses through RDKShell, with shift for upper case letters and symbols.
+    #[default]
+    Keys,
+    // Launch parameter deeplinked to the app; "{text}" is replaced by the URL encoded text.
+    Deeplink { parameter: String },
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AppCatalogEntry {
+    #[serde(skip)]
+    pub app_id: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub callsign: Option<String>,
+    pub friendly_name: Option<String>,
+    pub icon_url: Option<String>,
+    #[serde(default = "default_rdkshell_type")]
+    pub rdkshell_type: String,
+    pub available_type: Option<String>,
+    pub url: Option<String>,
+    pub uri: Option<String>,
+    pub configuration: Option<Value>,
+    #[serde(default = "default_content_template")]
+    pub content_template: String,
+    #[serde(default)]
+    pub parameter_encoding: ParameterEncoding,
+    #[serde(default, deserialize_with = "deserialize_deeplink")]
+    pub deeplink: Vec<DeeplinkStrategy>,
+    #[serde(default)]
+    pub lifecycle_timeouts: HashMap<String, u64>,
+    #[serde(default)]
+    pub background_exit: BackgroundExitPolicy,
+    #[serde(default)]
+    pub data_namespaces: Vec<String>,
+    #[serde(default)]
+    pub data_dirs: Vec<String>,
+    #[serde(default)]
+    pub relaunch_on_crash: bool,
+    #[serde(default)]
+    pub text_input: TextInputStrategy,
+}
+
+fn default_rdkshell_type() -> String {
+    "LightningApp".to_string()
+}
+
+fn default_content_template() -> String {
+    "contentId={contentId}".to_string()
+}
+
+impl AppCatalogEntry {
+    fn new(app_id: &str) -> AppCatalogEntry {
+        AppCatalogEntry {
+            app_id: app_id.to_string(),
+            aliases: vec![],
+            callsign: None,
+            friendly_name: None,
+            icon_url: None,
+            rdkshell_type: default_rdkshell_type(),
+            available_type: None,
+            url: None,
+            uri: None,
+            configuration: None,
+            content_template: default_content_template(),
+            parameter_encoding: ParameterEncoding::default(),
+            deeplink: vec![],
+            lifecycle_timeouts: HashMap::new(),
+            background_exit: BackgroundExitPolicy::default(),
+            data_namespaces: vec![],
+            data_dirs: vec![],
+            relaunch_on_crash: false,
+            text_input: TextInputStrategy::default(),
+        }
+    }
+
+    pub fn callsign(&self) -> String {
+        self.callsign.clone().unwrap_or(self.app_id.clone())
+    }
+
+    pub fn available_type(&self) -> &str {
+        self.available_type.as_deref().unwrap_or(&self.rdkshell_type)
+    }
+
+    // Applies the parameter encoding rules to the DAB launch parameters.
+    pub fn encode_parameters(&self, parameters: Vec<String>) -> Vec<String> {
+        if !self.parameter_encoding.decode {
+            return parameters;
+        }
+        parameters
+            .iter()
+            .map(|param| decode(param).map(|p| p.to_string()).unwrap_or(param.clone()))
+            .collect()
+    }
+
+    // Translates the contentId of launch-with-content into the launch parameter the app expects.
+    pub fn content_parameter(&self, content_id: &str) -> String {
+        self.content_template.replace("{contentId}", content_id)
+    }
+
+    pub fn join_parameters(&self, param_list: &[String]) -> String {
+        param_list.join(&self.parameter_encoding.separator)
+    }
+
+    // Expands the {placeholders} of a template string.
+    pub fn expand(&self, template: &str, param_list: &[String]) -> Result<String, DabError> {
+        let mut expanded = template
+            .replace("{appId}", &self.app_id)
+            .replace("{callsign}", &self.callsign())
+            .replace("{parameters}", &self.join_parameters(param_list));
+
+        if expanded.contains("{url}") {
+            let url = match &self.url {
+                Some(url) => self.expand(url, param_list)?,
+                None => String::new(),
+            };
+            expanded = expanded.replace("{url}", &url);
+        }
+
+        if expanded.contains("{language}") {
+            let language = get_rdk_language()
+                .inspect_err(|_| eprintln!("Unable to retrieve RDK language."))?;
+            expanded = expanded.replace("{language}", &language);
+        }
+
+        Ok(expanded)
+    }
+
+    // Expands the {placeholders} of every string in a JSON template.
+    pub fn expand_value(&self, template: &Value, param_list: &[String]) -> Result<Value, DabError> {
+        Ok(match template {
+            Value::String(s) => Value::String(self.expand(s, param_list)?),
+            Value::Array(list) => Value::Array(
+                list.iter()
+                    .map(|v| self.expand_value(v, param_list))
+                    .collect::<Result<Vec<Value>, DabError>>()?,
+            ),
+            Value::Object(map) => {
+                let mut expanded = serde_json::Map::new();
+                for (key, value) in map {
+                    expanded.insert(key.clone(), self.expand_value(value, param_list)?);
+                }
+                Value::Object(expanded)
+            }
+            other => other.clone(),
+        })
+    }
+}
+
+fn builtin_catalog() -> Value {
+    json!({
+        "YouTube": {
+            "aliases": ["Cobalt"],
+            "friendly_name": "YouTube",
+            "rdkshell_type": "Cobalt",
+            "available_type": "YouTube",
+            "url": "https://www.youtube.com/tv?{parameters}",
+            "configuration": { "url": "{url}", "language": "{language}" },
+            "content_template": "v={contentId}",
+            "parameter_encoding": { "decode": true },
+            "deeplink": { "type": "jsonrpc", "method": "{callsign}.1.deeplink", "params": "{url}" },
+            "text_input": { "type": "deeplink", "parameter": "search_query={text}" },
+            "lifecycle_timeouts": {
+                "cold_launch_timeout_ms": 6000,
+                "resume_launch_timeout_ms": 3000,
+                "exit_to_destroy_timeout_ms": 2500,
+                "exit_to_background_timeout_ms": 2000
+            }
+        },
+        "Netflix": {
+            "friendly_name": "Netflix",
+            "rdkshell_type": "Netflix",
+            "configuration": { "querystring": "{parameters}" },
+            "content_template": "m={contentId}",
+            "deeplink": [
+                { "type": "jsonrpc", "method": "{callsign}.1.systemcommand", "params": { "command": "{parameters}" } },
+                { "type": "jsonrpc", "method": "{callsign}.1.deeplink", "params": "{parameters}" },
+                { "type": "relaunch" }
+            ]
+        },
+        "PrimeVideo": {
+            "friendly_name": "Prime Video",
+            "rdkshell_type": "Amazon",
+            "deeplink": [
+                { "type": "jsonrpc", "method": "{callsign}.1.deeplink", "params": "{parameters}" },
+                { "type": "relaunch" }
+            ]
+        }
+    })
+}
+
+fn parse_catalog(catalog: Value) -> Result<Vec<AppCatalogEntry>, serde_json::Error> {
+    let entries = serde_json::from_value::<HashMap<String, AppCatalogEntry>>(catalog)?;
+    Ok(entries
+        .into_iter()
+        .map(|(app_id, mut entry)| {
+            entry.app_id = app_id;
+            entry
+        })
+        .collect())
+}
+
+lazy_static! {
+    // Keyed by lowercase appId.
+    static ref APP_CATALOG: HashMap<String, AppCatalogEntry> = {
+        let mut catalog = HashMap::new();
+        let mut catalog_file_found = false;
+
+        let mut add_entries = |entries: Vec<AppCatalogEntry>| {
+            for entry in entries {
+                catalog.insert(entry.app_id.to_lowercase(), entry);
+            }
+        };
+
+        if let Ok(json_file) = read_platform_config_json("/etc/dab/apps.json") {
+            catalog_file_found = true;
+            match serde_json::from_str::<Value>(&json_file).and_then(parse_catalog) {
+                Ok(entries) => {
+                    add_entries(entries);
+                    println!("Loaded app catalog from /etc/dab/apps.json");
+                },
+                Err(error) => {
+                    eprintln!("Error while parsing /etc/dab/apps.json {}", error);
+                }
+            }
+        }
+
+        if !catalog_file_found {
+            add_entries(parse_catalog(builtin_catalog()).unwrap());
+            println!("Default app catalog assigned");
+        }
+
+        if let Ok(json_file) = read_platform_config_json("/opt/dab_platform_apps.json") {
+            match serde_json::from_str::<Value>(&json_file).and_then(parse_catalog) {
+                Ok(entries) => {
+                    add_entries(entries);
+                    println!("Added app catalog from /opt/dab_platform_apps.json");
+                },
+                Err(error) => {
+                    eprintln!("Error while parsing /opt/dab_platform_apps.json {}", error);
+                }
+            }
+        }
+
+        catalog
+    };
+}
+
+// Returns the catalog entry for the appId (case-insensitive, aliases included). The entry keeps
+// the appId as requested, so it is the default callsign.
+pub fn get_app(app_id: &str) -> AppCatalogEntry {
+    let app_key = app_id.to_lowercase();
+    let entry = APP_CATALOG.get(&app_key).or_else(|| {
+        APP_CATALOG
+            .values()
+            .find(|entry| entry.aliases.iter().any(|alias| alias.to_lowercase() == app_key))
+    });
+
+    match entry {
+        Some(entry) => AppCatalogEntry {
+            app_id: app_id.to_string(),
+            ..entry.clone()
+        },
+        None => AppCatalogEntry::new(app_id),
+    }
+}
+
+// Returns the catalog app run by the RDKShell client, or an app named after it.
+pub fn get_app_by_callsign(callsign: &str) -> AppCatalogEntry {
+    APP_CATALOG
+        .values()
+        .find(|entry| entry.callsign().eq_ignore_ascii_case(callsign))
+        .cloned()
+        .unwrap_or_else(|| get_app(callsign))
+}
+
+pub fn get_catalog() -> Vec<AppCatalogEntry> {
+    let mut entries: Vec<AppCatalogEntry> = APP_CATALOG.values().cloned().collect();
+    entries.sort_by(|a, b| a.app_id.cmp(&b.app_id));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(app_id: &str, catalog: Value) -> AppCatalogEntry {
+        parse_catalog(catalog)
+            .unwrap()
+            .into_iter()
+            .find(|entry| entry.app_id == app_id)
+            .unwrap()
+    }
+
+    #[test]
+    fn builtin_catalog_parses() {
+        let catalog = parse_catalog(builtin_catalog()).unwrap();
+        let youtube = catalog.iter().find(|entry| entry.app_id == "YouTube").unwrap();
+        assert_eq!(youtube.aliases, ["Cobalt"]);
+        assert_eq!(youtube.rdkshell_type, "Cobalt");
+        assert_eq!(youtube.available_type(), "YouTube");
+        assert_eq!(youtube.lifecycle_timeouts["cold_launch_timeout_ms"], 6000);
+        assert!(matches!(&youtube.text_input, TextInputStrategy::Deeplink { parameter } if parameter == "search_query={text}"));
+    }
+
+    #[test]
+    fn catalog_entries_default_missing_fields() {
+        let app = entry("MyApp", json!({ "MyApp": { "url": "https://apps.example.com/?{parameters}" } }));
+        assert_eq!(app.callsign(), "MyApp");
+        assert_eq!(app.rdkshell_type, "LightningApp");
+        assert_eq!(app.available_type(), "LightningApp");
+        assert_eq!(app.content_parameter("42"), "contentId=42");
+        assert_eq!(app.background_exit, BackgroundExitPolicy::Suspend);
+        assert!(app.deeplink.is_empty());
+        assert!(!app.relaunch_on_crash);
+        assert!(matches!(app.text_input, TextInputStrategy::Keys));
+    }
+
+    #[test]
+    fn catalog_deeplink_takes_one_strategy_or_a_list() {
+        let app = entry("One", json!({ "One": { "deeplink": { "type": "relaunch" } } }));
+        assert!(matches!(app.deeplink[..], [DeeplinkStrategy::Relaunch]));
+
+        let app = entry("Many", json!({ "Many": { "deeplink": [
+            { "type": "jsonrpc", "method": "{callsign}.1.deeplink", "params": "{parameters}" },
+            { "type": "relaunch" }
+        ] } }));
+        assert!(matches!(app.deeplink[..], [DeeplinkStrategy::Jsonrpc { .. }, DeeplinkStrategy::Relaunch]));
+
+        assert!(parse_catalog(json!({ "Bad": { "deeplink": { "type": "unknown" } } })).is_err());
+    }
+
+    #[test]
+    fn catalog_background_exit_takes_a_known_policy() {
+        let app = entry("MyApp", json!({ "MyApp": { "background_exit": "hibernate" } }));
+        assert_eq!(app.background_exit, BackgroundExitPolicy::Hibernate);
+
+        assert!(parse_catalog(json!({ "Bad": { "background_exit": "pause" } })).is_err());
+    }
+
+    #[test]
+    fn catalog_templates_expand() {
+        let app = entry("MyApp", json!({ "MyApp": {
+            "callsign": "MyAppPlugin",
+            "url": "https://apps.example.com/{appId}?{parameters}",
+            "parameter_encoding": { "decode": true, "separator": ";" }
+        } }));
+
+        let params = app.encode_parameters(vec!["a%3Db".to_string(), "c=d".to_string()]);
+        assert_eq!(params, ["a=b", "c=d"]);
+        assert_eq!(
+            app.expand("{callsign}:{url}", &params).unwrap(),
+            "MyAppPlugin:https://apps.example.com/MyApp?a=b;c=d"
+        );
+        assert_eq!(
+            app.expand_value(&json!({ "command": ["{parameters}", 1] }), &params).unwrap(),
+            json!({ "command": ["a=b;c=d", 1] })
+        );
+    }
+}
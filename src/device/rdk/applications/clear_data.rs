@@ -0,0 +1,142 @@
+use crate::dab::structs::ClearApplicationDataRequest;
+use crate::dab::structs::ClearApplicationDataResponse;
+use crate::dab::structs::DabError;
+use crate::device::rdk::applications::catalog::get_app;
+use crate::device::rdk::applications::exit::destroy_app;
+use crate::device::rdk::applications::get_state::AppState;
+use crate::device::rdk::applications::get_state::get_app_state;
+use crate::device::rdk::applications::launch::wait_till_app_stops;
+use crate::device::rdk::interface::rdk_request_with_params;
+use crate::device::rdk::interface::{get_app_lifecycle_backend, AppLifecycleBackend};
+use crate::device::rdk::interface::{is_local_device, thunder_plugin_exists};
+use serde_json::{json, Value};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+#[allow(non_snake_case)]
+#[allow(dead_code)]
+#[allow(unused_mut)]
+pub fn process(_dab_request: ClearApplicationDataRequest) -> Result<String, DabError> {
+    let mut ResponseOperator = ClearApplicationDataResponse::default();
+    // *** Fill in the fields of the struct ClearApplicationDataResponse here ***
+
+    if _dab_request.appId.is_empty() {
+        return Err(DabError::Err400(
+            "request missing 'appId' parameter".to_string(),
+        ));
+    }
+
+    let app = get_app(&_dab_request.appId);
+    let callsign = app.callsign();
+
+    // The app must not be running while its data is removed.
+    if !matches!(get_app_state(&callsign)?, AppState::Stopped) {
+        destroy_app(&callsign)?;
+        wait_till_app_stops(&callsign)?;
+    }
+
+    if get_app_lifecycle_backend() == AppLifecycleBackend::AppManager {
+        let _rdkresponse: Value = rdk_request_with_params(
+            "org.rdk.AppManager.clearAppData",
+            json!({ "appId": callsign }),
+        )?;
+        ResponseOperator.cleared.push(format!("AppManager:{}", callsign));
+    }
+
+    if thunder_plugin_exists("org.rdk.PersistentStore") {
+        let namespaces = if app.data_namespaces.is_empty() {
+            vec![callsign.clone()]
+        } else {
+            app.data_namespaces
+                .iter()
+                .map(|namespace| app.expand(namespace, &[]))
+                .collect::<Result<Vec<String>, DabError>>()?
+        };
+
+        for namespace in namespaces {
+            let response: Result<Value, DabError> = rdk_request_with_params(
+                "org.rdk.PersistentStore.deleteNamespace",
+                json!({ "namespace": namespace }),
+            );
+            match response {
+                Ok(_) => ResponseOperator.cleared.push(format!("PersistentStore:{}", namespace)),
+                Err(err) if is_missing_data_error(&err) => {
+                    println!("No PersistentStore namespace {} to delete.", namespace);
+                }
+                Err(err) => return Err(clear_error(&format!("PersistentStore namespace {}", namespace), err)),
+            }
+        }
+    }
+
+    if thunder_plugin_exists("org.rdk.StorageManager") {
+        let response: Result<Value, DabError> = rdk_request_with_params(
+            "org.rdk.StorageManager.clear",
+            json!({ "appId": callsign }),
+        );
+        match response {
+            Ok(_) => ResponseOperator.cleared.push(format!("StorageManager:{}", callsign)),
+            Err(err) if is_missing_data_error(&err) => {
+                println!("No StorageManager storage for {} to clear.", callsign);
+            }
+            Err(err) => return Err(clear_error(&format!("StorageManager storage of {}", callsign), err)),
+        }
+    }
+
+    if is_local_device() {
+        for dir in app.data_dirs.iter() {
+            let dir = app.expand(dir, &[])?;
+            if clear_dir(Path::new(&dir))? {
+                ResponseOperator.cleared.push(dir);
+            }
+        }
+    }
+
+    // *******************************************************************
+    Ok(serde_json::to_string(&ResponseOperator).unwrap())
+}
+
+// Thunder errors telling that there is no such namespace or storage; any other error may leave
+// the data in place.
+fn is_missing_data_error(err: &DabError) -> bool {
+    let message = match err {
+        DabError::Err400(msg) | DabError::Err500(msg) | DabError::Err501(msg) => msg,
+    };
+    ["ERROR_NOT_EXIST", "ERROR_UNKNOWN_KEY", "not found", "does not exist"]
+        .iter()
+        .any(|marker| message.contains(marker))
+}
+
+fn clear_error(what: &str, err: DabError) -> DabError {
+    let message = match err {
+        DabError::Err400(msg) | DabError::Err500(msg) | DabError::Err501(msg) => msg,
+    };
+    DabError::Err500(format!("Failed to clear {}: {}", what, message))
+}
+
+// Removes the contents of the directory, keeping the directory itself. Returns false when there
+// is no such directory.
+fn clear_dir(dir: &Path) -> Result<bool, DabError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(false),
+        Err(e) => {
+            return Err(DabError::Err500(format!("Failed to read {}: {}", dir.display(), e)));
+        }
+    };
+
+    for entry in entries {
+        let path = entry
+            .map_err(|e| DabError::Err500(format!("Failed to read {}: {}", dir.display(), e)))?
+            .path();
+        let result = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        result.map_err(|e| {
+            DabError::Err500(format!("Failed to remove {}: {}", path.display(), e))
+        })?;
+    }
+    Ok(true)
+}
@@ -0,0 +1,55 @@
+use crate::dab::structs::ApplicationClient;
+use crate::dab::structs::ApplicationClientsRequest;
+use crate::dab::structs::ApplicationClientsResponse;
+use crate::dab::structs::DabError;
+use crate::device::rdk::applications::get_state::get_rdkshell_runtimes;
+use crate::device::rdk::applications::launch::{get_visibility, get_z_order};
+use crate::device::rdk::input::key::{get_focused_client, is_system_client};
+use crate::device::rdk::interface::get_client_memory;
+
+#[allow(non_snake_case)]
+#[allow(dead_code)]
+#[allow(unused_mut)]
+pub fn process(_dab_request: ApplicationClientsRequest) -> Result<String, DabError> {
+    let mut ResponseOperator = ApplicationClientsResponse::default();
+    // *** Fill in the fields of the struct ApplicationClientsResponse here ***
+
+    let z_order = get_z_order()?;
+    let runtimes = get_rdkshell_runtimes()?;
+    let focused_client = get_focused_client();
+
+    // Clients with a display in Z order, then the runtimes without one, e.g. hibernated apps.
+    let mut clients: Vec<(String, Option<usize>)> = z_order
+        .iter()
+        .enumerate()
+        .map(|(index, client)| (client.clone(), Some(index)))
+        .collect();
+    for runtime in runtimes.iter() {
+        if !z_order.iter().any(|c| c.eq_ignore_ascii_case(&runtime.callsign)) {
+            clients.push((runtime.callsign.clone(), None));
+        }
+    }
+
+    for (client, z_order_index) in clients {
+        let state = runtimes
+            .iter()
+            .find(|runtime| runtime.callsign.eq_ignore_ascii_case(&client))
+            .map(|runtime| runtime.state.clone());
+        let visible = z_order_index.and_then(|_| get_visibility(client.clone()).ok());
+
+        ResponseOperator.clients.push(ApplicationClient {
+            focused: focused_client
+                .as_ref()
+                .is_some_and(|focused| focused.eq_ignore_ascii_case(&client)),
+            zOrder: z_order_index,
+            visible,
+            state,
+            memoryKb: get_client_memory(&client).ok(),
+            system: is_system_client(&client),
+            client,
+        });
+    }
+
+    // *******************************************************************
+    Ok(serde_json::to_string(&ResponseOperator).unwrap())
+}
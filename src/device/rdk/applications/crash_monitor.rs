@@ -0,0 +1,159 @@
+// Detects apps that terminate abnormally: the Thunder Controller deactivates their plugin with a
+// crash reason, i.e. its process died ("Failure") or stopped answering ("WatchdogExpired"). Apps
+// closed on request, by the launcher, by the adapter or by the platform's memory management, are
+// deactivated with other reasons and are not crashes. Plugins the Thunder Monitor plugin
+// deactivates are reported too, without a relaunch. Each one is published as a warning on
+// dab/<device-id>/messages.
+
+use crate::dab::notify;
+use crate::dab::structs::NotificationLevel;
+use crate::device::rdk::applications::catalog::get_app_by_callsign;
+use crate::device::rdk::applications::launch::launch_app;
+use crate::device::rdk::applications::state_cache::RuntimeState;
+use lazy_static::lazy_static;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Stops expected within this time after a destroy request are not reported.
+const EXPECTED_STOP_WINDOW: Duration = Duration::from_secs(30);
+// Controller deactivation reasons of a plugin whose process ended abnormally.
+const CRASH_REASONS: [&str; 2] = ["Failure", "WatchdogExpired"];
+// At most this many relaunches of an app within the window, so that crash loops end.
+const MAX_RELAUNCHES: usize = 3;
+const RELAUNCH_WINDOW: Duration = Duration::from_secs(300);
+const RELAUNCH_DELAY: Duration = Duration::from_secs(1);
+
+lazy_static! {
+    // Keyed by lowercase callsign.
+    static ref EXPECTED_STOPS: Mutex<HashMap<String, Instant>> = Mutex::new(HashMap::new());
+    static ref RELAUNCHES: Mutex<HashMap<String, Vec<Instant>>> = Mutex::new(HashMap::new());
+    // State of each app when RDKShell last reported it stopped; the Controller event may follow.
+    static ref STOPPED_FROM: Mutex<HashMap<String, (RuntimeState, Instant)>> = Mutex::new(HashMap::new());
+}
+
+// Called before the adapter destroys an app, so that its stop is not taken for a crash.
+pub fn expect_stop(callsign: &str) {
+    let mut expected = EXPECTED_STOPS.lock().unwrap();
+    expected.retain(|_, since| since.elapsed() < EXPECTED_STOP_WINDOW);
+    expected.insert(callsign.to_lowercase(), Instant::now());
+}
+
+fn take_expected_stop(callsign: &str) -> bool {
+    EXPECTED_STOPS
+        .lock()
+        .unwrap()
+        .remove(&callsign.to_lowercase())
+        .is_some_and(|since| since.elapsed() < EXPECTED_STOP_WINDOW)
+}
+
+// Called on RDKShell events stopping an app that was in `last_state`.
+pub fn app_stopped(callsign: &str, last_state: RuntimeState) {
+    let mut stopped = STOPPED_FROM.lock().unwrap();
+    stopped.retain(|_, (_, since)| since.elapsed() < EXPECTED_STOP_WINDOW);
+    stopped.insert(callsign.to_lowercase(), (last_state, Instant::now()));
+}
+
+fn take_stopped_from(callsign: &str) -> Option<RuntimeState> {
+    STOPPED_FROM
+        .lock()
+        .unwrap()
+        .remove(&callsign.to_lowercase())
+        .filter(|(_, since)| since.elapsed() < EXPECTED_STOP_WINDOW)
+        .map(|(state, _)| state)
+}
+
+// Called on the Controller "statechange" event, e.g. {"callsign", "state": "Deactivated",
+// "reason": "Failure"}. `state` is the app's state in the cache, which RDKShell may already have
+// moved to Stopped.
+pub fn plugin_state_changed(params: &Value, state: Option<RuntimeState>) {
+    let Some(callsign) = params["callsign"].as_str() else {
+        return;
+    };
+    if !params["state"].as_str().unwrap_or_default().eq_ignore_ascii_case("deactivated") {
+        return;
+    }
+    let reason = params["reason"].as_str().unwrap_or_default();
+    let crashed = CRASH_REASONS.iter().any(|r| r.eq_ignore_ascii_case(reason));
+
+    let stopped_from = take_stopped_from(callsign);
+    if !crashed || take_expected_stop(callsign) {
+        return;
+    }
+    let last_state = match state {
+        Some(RuntimeState::Stopped) | None => stopped_from,
+        state => state,
+    };
+
+    let app = get_app_by_callsign(callsign);
+    notify(
+        NotificationLevel::warn,
+        &format!(
+            "Application {} terminated unexpectedly ({}); last known state: {}",
+            app.app_id,
+            reason,
+            last_state.map_or("unknown".to_string(), |state| format!("{:?}", state))
+        ),
+    );
+
+    if app.relaunch_on_crash && last_state == Some(RuntimeState::Running) {
+        relaunch(callsign);
+    }
+}
+
+// Called on the Monitor plugin "action" event, e.g. {"callsign", "action": "Deactivate", "reason"}.
+pub fn monitor_action(params: &Value, last_state: Option<RuntimeState>) {
+    let Some(callsign) = params["callsign"].as_str() else {
+        return;
+    };
+    let action = params["action"].as_str().unwrap_or_default();
+    // Activations follow the restart of a plugin that was already reported.
+    if action.eq_ignore_ascii_case("activate") {
+        return;
+    }
+
+    // The RDKShell destroy event that follows was caused by the Monitor.
+    expect_stop(callsign);
+
+    let app = get_app_by_callsign(callsign);
+    let last_state = last_state.map_or("unknown".to_string(), |state| format!("{:?}", state));
+    notify(
+        NotificationLevel::warn,
+        &format!(
+            "Application {} was stopped by the Monitor plugin ({}: {}); last known state: {}",
+            app.app_id,
+            action,
+            params["reason"].as_str().unwrap_or("unknown reason"),
+            last_state
+        ),
+    );
+}
+
+fn relaunch(callsign: &str) {
+    {
+        let mut relaunches = RELAUNCHES.lock().unwrap();
+        let history = relaunches.entry(callsign.to_lowercase()).or_default();
+        history.retain(|at| at.elapsed() < RELAUNCH_WINDOW);
+        if history.len() >= MAX_RELAUNCHES {
+            println!("Not relaunching {}; it was relaunched {} times in the last {} seconds.",
+                callsign, history.len(), RELAUNCH_WINDOW.as_secs());
+            return;
+        }
+        history.push(Instant::now());
+    }
+
+    // Launches wait on the lifecycle events, so they can't run on the event thread.
+    let app = get_app_by_callsign(callsign);
+    thread::spawn(move || {
+        thread::sleep(RELAUNCH_DELAY);
+        match launch_app(&app, vec![]) {
+            Ok(_) => notify(
+                NotificationLevel::info,
+                &format!("Application {} relaunched after terminating unexpectedly", app.app_id),
+            ),
+            Err(err) => println!("Failed to relaunch {}: {:?}", app.app_id, err),
+        }
+    });
+}
@@ -1,17 +1,26 @@
 use crate::dab::structs::DabError;
 use crate::dab::structs::ExitApplicationRequest;
 use crate::dab::structs::ExitApplicationResponse;
+use crate::dab::structs::ExitTiming;
+use crate::device::rdk::applications::app_manager;
+use crate::device::rdk::applications::catalog::{get_app, BackgroundExitPolicy};
 use crate::device::rdk::applications::get_state::AppState;
 use crate::device::rdk::applications::get_state::get_app_state;
 use crate::device::rdk::applications::get_state::get_dab_app_state;
-use crate::device::rdk::applications::launch::{rdkshell_suspend, rdkshell_destroy};
+use crate::device::rdk::applications::launch::{rdkshell_suspend, rdkshell_destroy, rdkshell_hibernate};
+use crate::device::rdk::applications::launch::{elapsed_ms, publish_lifecycle_metrics, EXIT_MIN_WAIT_MS};
+use crate::device::rdk::applications::state_cache;
+use crate::device::rdk::applications::state_cache::RuntimeState;
 use crate::device::rdk::interface::get_lifecycle_timeout;
+use crate::device::rdk::interface::{get_app_lifecycle_backend, AppLifecycleBackend};
+use std::time::Instant;
 use std::{thread, time};
 
 #[allow(non_snake_case)]
 #[allow(dead_code)]
 #[allow(unused_mut)]
 pub fn process(_dab_request: ExitApplicationRequest) -> Result<String, DabError> {
+    let started = Instant::now();
     let mut ResponseOperator = ExitApplicationResponse::default();
     if _dab_request.appId.is_empty() {
         return Err(DabError::Err400(
@@ -22,56 +31,144 @@ pub fn process(_dab_request: ExitApplicationRequest) -> Result<String, DabError>
     // background default is false
     let to_background = _dab_request.background.unwrap_or(false);
 
+    let app = get_app(&_dab_request.appId);
+    let callsign = app.callsign();
+
+    // Apps with the destroy policy are stopped even when asked to go to the background.
+    let policy = app.background_exit;
+    let destroy = !to_background || policy == BackgroundExitPolicy::Destroy;
+    let mut timing = ExitTiming {
+        exitType: if destroy { "destroy" } else { "background" }.to_string(),
+        ..Default::default()
+    };
+
     let mut was_stopped = false;
-    let app_state = get_app_state(&_dab_request.appId)?;
+    let app_state = get_app_state(&callsign)?;
     match app_state {
         AppState::Visible | AppState::Invisible | AppState::Suspended => {
-            if to_background {
-                rdkshell_suspend(_dab_request.appId.clone())?;
+            if destroy {
+                destroy_app(&callsign)?;
+            } else if policy == BackgroundExitPolicy::Hibernate {
+                hibernate_app(&callsign, &app_state)?;
             } else {
-                rdkshell_destroy(_dab_request.appId.clone())?;
+                suspend_app(&callsign)?;
             }
         },
         AppState::Hibernated => {
-            if to_background == false {
-                rdkshell_destroy(_dab_request.appId.clone())?;
+            if destroy {
+                destroy_app(&callsign)?;
             }
         },
         AppState::Stopped => {
             was_stopped = true;
         },
     }
+    if !was_stopped {
+        timing.requestAcceptedMs = Some(elapsed_ms(started));
+    }
+
+    let timeout_type = if destroy {
+        "exit_to_destroy_timeout_ms"
+    } else {
+        "exit_to_background_timeout_ms"
+    };
+
+    // With lifecycle events the exit completes on the transition; the lifecycle timeout is the
+    // upper bound, but never less than the time given to polling.
+    if !was_stopped || destroy {
+        let lifecycle_timeout = get_lifecycle_timeout(&_dab_request.appId.to_lowercase(), timeout_type);
+        let wait_time = time::Duration::from_millis(lifecycle_timeout.max(EXIT_MIN_WAIT_MS));
+        let exited = |state: RuntimeState| {
+            if destroy {
+                state == RuntimeState::Stopped
+            } else if policy == BackgroundExitPolicy::Hibernate {
+                state == RuntimeState::Hibernated
+            } else {
+                matches!(state, RuntimeState::Suspended | RuntimeState::Hibernated)
+            }
+        };
+
+        if let Some(reached) = state_cache::wait_for_state(&callsign, wait_time, exited) {
+            // An app that was already stopped didn't exit, so there is nothing to time.
+            if reached && !was_stopped {
+                timing.exitedMs = Some(elapsed_ms(started));
+            }
+            ResponseOperator.state = get_dab_app_state(callsign.clone())?;
+            if !was_stopped {
+                publish_lifecycle_metrics(&_dab_request.appId, "exit", &timing);
+            }
+            ResponseOperator.timing = Some(timing);
+            return Ok(serde_json::to_string(&ResponseOperator).unwrap());
+        }
+    }
 
     // *******************************************************************
     for _idx in 1..=8 {
         // 2 seconds (8*250ms)
-        // TODO: refactor to listen to Thunder events with websocket.
         thread::sleep(time::Duration::from_millis(250));
 
-        if was_stopped && to_background {
+        if was_stopped && !destroy {
             println!("{} was already STOPPED before putting to BACKGROUND; Exiting loop.", _dab_request.appId);
-            wait_till_app_exit_timeout(&_dab_request.appId, "exit_to_background_timeout_ms");
+            wait_till_app_exit_timeout(&_dab_request.appId, timeout_type);
             break;
         }
 
-        ResponseOperator.state = get_dab_app_state(_dab_request.appId.clone())?;
+        ResponseOperator.state = get_dab_app_state(callsign.clone())?;
 
-        if is_state_match(&ResponseOperator.state, to_background) {
-            let timeout_type = if to_background {
-                "exit_to_background_timeout_ms"
-            } else {
-                "exit_to_destroy_timeout_ms"
-            };
-            
+        if is_state_match(&ResponseOperator.state, !destroy) {
+            if !was_stopped {
+                timing.exitedMs = Some(elapsed_ms(started));
+            }
             wait_till_app_exit_timeout(&_dab_request.appId, timeout_type);
             break;
         }
     }
+    if !was_stopped {
+        publish_lifecycle_metrics(&_dab_request.appId, "exit", &timing);
+    }
+    ResponseOperator.timing = Some(timing);
     Ok(serde_json::to_string(&ResponseOperator).unwrap())
 }
 
+pub fn suspend_app(app_id: &str) -> Result<(), DabError> {
+    match get_app_lifecycle_backend() {
+        AppLifecycleBackend::AppManager => app_manager::close_app(app_id),
+        AppLifecycleBackend::RDKShell => rdkshell_suspend(app_id.to_string()).map(|_| ()),
+    }
+}
+
+// RDKShell only hibernates suspended apps. AppManager has no hibernation; the app is suspended.
+pub fn hibernate_app(app_id: &str, app_state: &AppState) -> Result<(), DabError> {
+    if get_app_lifecycle_backend() == AppLifecycleBackend::AppManager {
+        return app_manager::close_app(app_id);
+    }
+
+    if !matches!(app_state, AppState::Suspended) {
+        rdkshell_suspend(app_id.to_string())?;
+        let wait_time = time::Duration::from_millis(EXIT_MIN_WAIT_MS);
+        let suspended = |state: RuntimeState| state == RuntimeState::Suspended;
+        if state_cache::wait_for_state(app_id, wait_time, suspended).is_none() {
+            for _idx in 1..=8 {
+                if let AppState::Suspended = get_app_state(app_id)? {
+                    break;
+                }
+                thread::sleep(time::Duration::from_millis(250));
+            }
+        }
+    }
+
+    rdkshell_hibernate(app_id.to_string())
+}
+
+pub fn destroy_app(app_id: &str) -> Result<(), DabError> {
+    match get_app_lifecycle_backend() {
+        AppLifecycleBackend::AppManager => app_manager::terminate_app(app_id),
+        AppLifecycleBackend::RDKShell => rdkshell_destroy(app_id.to_string()).map(|_| ()),
+    }
+}
+
 fn wait_till_app_exit_timeout(app_id: &str, timeout_type: &str) {
-    let sleep_time = get_lifecycle_timeout(&app_id.to_lowercase(), timeout_type).unwrap_or(2500);
+    let sleep_time = get_lifecycle_timeout(&app_id.to_lowercase(), timeout_type);
     std::thread::sleep(time::Duration::from_millis(sleep_time));
 }
 
@@ -0,0 +1,49 @@
+use crate::dab::structs::DabError;
+use crate::dab::structs::FocusApplicationRequest;
+use crate::dab::structs::FocusApplicationResponse;
+use crate::device::rdk::applications::catalog::get_app;
+use crate::device::rdk::applications::get_state::AppState;
+use crate::device::rdk::applications::get_state::get_app_state;
+use crate::device::rdk::applications::launch::{move_to_front, set_focus};
+use crate::device::rdk::input::key::get_focused_client;
+use crate::device::rdk::interface::{get_app_lifecycle_backend, AppLifecycleBackend};
+
+#[allow(non_snake_case)]
+#[allow(dead_code)]
+#[allow(unused_mut)]
+pub fn process(_dab_request: FocusApplicationRequest) -> Result<String, DabError> {
+    let mut ResponseOperator = FocusApplicationResponse::default();
+    // *** Fill in the fields of the struct FocusApplicationResponse here ***
+
+    if _dab_request.appId.is_empty() {
+        return Err(DabError::Err400(
+            "request missing 'appId' parameter".to_string(),
+        ));
+    }
+
+    if get_app_lifecycle_backend() == AppLifecycleBackend::AppManager {
+        return Err(DabError::Err501(
+            "applications/focus requires org.rdk.RDKShell.".to_string(),
+        ));
+    }
+
+    let callsign = get_app(&_dab_request.appId).callsign();
+    if let AppState::Stopped = get_app_state(&callsign)? {
+        return Err(DabError::Err400(
+            format!("{} is not running.", _dab_request.appId),
+        ));
+    }
+
+    // Both are done unless asked otherwise.
+    if _dab_request.moveToFront.unwrap_or(true) {
+        move_to_front(&callsign)?;
+    }
+    if _dab_request.setFocus.unwrap_or(true) {
+        set_focus(&callsign)?;
+    }
+
+    ResponseOperator.focusedClient = get_focused_client();
+
+    // *******************************************************************
+    Ok(serde_json::to_string(&ResponseOperator).unwrap())
+}
@@ -1,7 +1,12 @@
 use crate::dab::structs::DabError;
 use crate::dab::structs::GetApplicationStateRequest;
 use crate::dab::structs::GetApplicationStateResponse;
+use crate::device::rdk::applications::app_manager;
+use crate::device::rdk::applications::catalog::get_app;
+use crate::device::rdk::applications::state_cache;
+use crate::device::rdk::applications::state_cache::RuntimeState;
 use crate::device::rdk::interface::rdk_request;
+use crate::device::rdk::interface::{get_app_lifecycle_backend, AppLifecycleBackend};
 use crate::device::rdk::interface::RdkResponse;
 use crate::hw_specific::applications::launch::get_visibility;
 use serde::Deserialize;
@@ -63,42 +68,24 @@ pub fn get_dab_app_state(callsign: String) -> Result<String, DabError> {
 }
 
 pub fn get_app_state(callsign: &str) -> Result<AppState, DabError> {
-    #[derive(Deserialize)]
-    #[allow(dead_code)]
-    struct State {
-        callsign: String,
-        state: String,
-        #[serde(default)]
-        uri: String,
+    if get_app_lifecycle_backend() == AppLifecycleBackend::AppManager {
+        return app_manager::get_app_state(callsign);
     }
 
-    #[derive(Deserialize)]
-    #[allow(dead_code)]
-    struct GetState {
-        // Different RDKShell versions return either `result.state` or `result.runtimes`.
-        // Accept both so the adapter is portable across builds.
-        #[serde(default, alias = "state", alias = "runtimes")]
-        runtimes: Vec<State>,
-        success: bool,
+    match state_cache::get_state(callsign) {
+        Some(RuntimeState::Stopped) => return Ok(AppState::Stopped),
+        Some(RuntimeState::Hibernated) => return Ok(AppState::Hibernated),
+        Some(RuntimeState::Suspended) => return Ok(AppState::Suspended),
+        Some(RuntimeState::Running) => return visible_app_state(callsign),
+        None => {}
     }
 
-    let rdkresponse: RdkResponse<GetState> = rdk_request("org.rdk.RDKShell.getState")?;
-
-    for item in rdkresponse.result.runtimes {
-        if item.callsign == callsign {
-            match item.state.as_str() {
-                "suspended" => return Ok(AppState::Suspended),
-                "hibernated" => return Ok(AppState::Hibernated),
-                "activated" | "resumed" => {
-                    // Launch request mandates that application should be focused and visible.
-                    let visibility = get_visibility(callsign.to_owned())?;
-                    let app_state = if visibility {
-                        AppState::Visible
-                    } else {
-                        AppState::Invisible
-                    };
-                    return Ok(app_state);
-                },
+    for item in get_rdkshell_runtimes()? {
+        if item.callsign.eq_ignore_ascii_case(callsign) {
+            match item.runtime_state() {
+                Some(RuntimeState::Suspended) => return Ok(AppState::Suspended),
+                Some(RuntimeState::Hibernated) => return Ok(AppState::Hibernated),
+                Some(RuntimeState::Running) => return visible_app_state(callsign),
                 _ => {
                     println!("Implement verification of: {} App state: {}",
                         callsign, item.state.as_str());
@@ -114,6 +101,55 @@ pub fn get_app_state(callsign: &str) -> Result<AppState, DabError> {
     Ok(AppState::Stopped)
 }
 
+// RDKShell sends no visibility events, so the visibility of running apps is always queried.
+fn visible_app_state(callsign: &str) -> Result<AppState, DabError> {
+    // Launch request mandates that application should be focused and visible.
+    let visibility = get_visibility(callsign.to_owned())?;
+    let app_state = if visibility {
+        AppState::Visible
+    } else {
+        AppState::Invisible
+    };
+    Ok(app_state)
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+pub struct RDKShellRuntime {
+    pub callsign: String,
+    pub state: String,
+    #[serde(default)]
+    pub uri: String,
+}
+
+impl RDKShellRuntime {
+    // Transitional states are reported as the state being entered; apps being restored are
+    // still hibernated.
+    pub fn runtime_state(&self) -> Option<RuntimeState> {
+        match self.state.to_lowercase().as_str() {
+            "activated" | "resumed" | "activating" | "resuming" => Some(RuntimeState::Running),
+            "suspended" | "suspending" => Some(RuntimeState::Suspended),
+            "hibernated" | "hibernating" | "checkpointed" | "restoring" => Some(RuntimeState::Hibernated),
+            _ => None,
+        }
+    }
+}
+
+pub fn get_rdkshell_runtimes() -> Result<Vec<RDKShellRuntime>, DabError> {
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct GetState {
+        // Different RDKShell versions return either `result.state` or `result.runtimes`.
+        // Accept both so the adapter is portable across builds.
+        #[serde(default, alias = "state", alias = "runtimes")]
+        runtimes: Vec<RDKShellRuntime>,
+        success: bool,
+    }
+
+    let rdkresponse: RdkResponse<GetState> = rdk_request("org.rdk.RDKShell.getState")?;
+    Ok(rdkresponse.result.runtimes)
+}
+
 #[allow(non_snake_case)]
 #[allow(dead_code)]
 #[allow(unused_mut)]
@@ -127,7 +163,7 @@ pub fn process(_dab_request: GetApplicationStateRequest) -> Result<String, DabEr
         ));
     }
 
-    ResponseOperator.state = get_dab_app_state(_dab_request.appId.clone())?;
+    ResponseOperator.state = get_dab_app_state(get_app(&_dab_request.appId).callsign())?;
 
     // *******************************************************************
     Ok(serde_json::to_string(&ResponseOperator).unwrap())
@@ -0,0 +1,52 @@
+use crate::dab::notify;
+use crate::dab::structs::DabError;
+use crate::dab::structs::InstallApplicationRequest;
+use crate::dab::structs::InstallApplicationResponse;
+use crate::dab::structs::NotificationLevel;
+use crate::device::rdk::applications::packages::install_package;
+
+// Version given to the package manager when the request doesn't name one.
+const DEFAULT_PACKAGE_VERSION: &str = "1.0.0";
+
+#[allow(non_snake_case)]
+#[allow(dead_code)]
+#[allow(unused_mut)]
+pub fn process(_dab_request: InstallApplicationRequest) -> Result<String, DabError> {
+    let mut ResponseOperator = InstallApplicationResponse::default();
+    // *** Fill in the fields of the struct InstallApplicationResponse here ***
+
+    if _dab_request.url.is_empty() {
+        return Err(DabError::Err400(
+            "request missing 'url' parameter".to_string(),
+        ));
+    }
+
+    // Without an appId, the package file name (up to the first '.') is used.
+    let app_id = match _dab_request.appId {
+        Some(app_id) if !app_id.is_empty() => app_id,
+        _ => _dab_request
+            .url
+            .rsplit('/')
+            .next()
+            .and_then(|file_name| file_name.split('.').next())
+            .unwrap_or("")
+            .to_string(),
+    };
+    if app_id.is_empty() {
+        return Err(DabError::Err400(
+            "unable to name the app after the 'url'; pass the 'appId' parameter".to_string(),
+        ));
+    }
+
+    let version = _dab_request
+        .version
+        .unwrap_or(DEFAULT_PACKAGE_VERSION.to_string());
+
+    install_package(&app_id, &version, &_dab_request.url)?;
+    notify(NotificationLevel::info, &format!("{} {} installed", app_id, version));
+
+    ResponseOperator.appId = app_id;
+
+    // *******************************************************************
+    Ok(serde_json::to_string(&ResponseOperator).unwrap())
+}
@@ -1,16 +1,34 @@
+use crate::dab::publish;
 use crate::dab::structs::DabError;
 use crate::dab::structs::LaunchApplicationRequest;
+use crate::dab::structs::LaunchApplicationResponse;
+use crate::dab::structs::LaunchTiming;
+use crate::device::rdk::applications::app_manager;
+use crate::device::rdk::applications::catalog::{get_app, AppCatalogEntry, DeeplinkStrategy};
+use crate::device::rdk::applications::crash_monitor;
 use crate::device::rdk::applications::get_state::AppState;
 use crate::device::rdk::applications::get_state::get_app_state;
 use crate::device::rdk::applications::get_state::get_dab_app_state;
+use crate::device::rdk::applications::launch_policy;
+use crate::device::rdk::applications::state_cache;
+use crate::device::rdk::applications::state_cache::RuntimeState;
 use crate::device::rdk::interface::http_post;
+use crate::device::rdk::interface::rdk_request;
+use crate::device::rdk::interface::rdk_request_with_params;
+use crate::device::rdk::interface::RdkResponse;
 use crate::device::rdk::interface::get_lifecycle_timeout;
-use crate::device::rdk::system::settings::get::get_rdk_language;
+use crate::device::rdk::interface::get_lifecycle_metrics_enabled;
+use crate::device::rdk::interface::{get_app_lifecycle_backend, AppLifecycleBackend};
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use serde_json::{json, Value};
 
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use std::{thread, time};
-use urlencoding::decode;
+
+// Least time an app is given to start or stop, and to exit, when waiting on lifecycle events; what
+// polling covers at 250ms per check.
+pub const LAUNCH_MIN_WAIT_MS: u64 = 5000;
+pub const EXIT_MIN_WAIT_MS: u64 = 2000;
 
 #[derive(Serialize, Clone)]
 pub struct RDKShellRequestParams {
@@ -29,40 +47,54 @@ pub struct RdkRequest<T> {
 #[allow(dead_code)]
 #[allow(unused_mut)]
 pub fn process(_dab_request: LaunchApplicationRequest) -> Result<String, DabError> {
+    let mut ResponseOperator = LaunchApplicationResponse::default();
     if _dab_request.appId.is_empty() {
         return Err(DabError::Err400(
             "request missing 'appId' parameter".to_string(),
         ));
     }
 
-    let launch_req_params = RDKShellRequestParams {
-        callsign: _dab_request.appId.clone(),
-    };
+    let app = get_app(&_dab_request.appId);
+    let param_list = app.encode_parameters(_dab_request.parameters.clone().unwrap_or_default());
 
-    let is_cobalt = _dab_request.appId.to_lowercase() == "cobalt"
-        || _dab_request.appId.to_lowercase() == "youtube";
+    let timing = launch_app(&app, param_list)?;
+    publish_lifecycle_metrics(&_dab_request.appId, "launch", &timing);
+    ResponseOperator.timing = Some(timing);
 
-    let is_netflix = _dab_request.appId.to_lowercase() == "netflix";
+    Ok(serde_json::to_string(&ResponseOperator).unwrap())
+}
 
-    let mut param_list = vec![];
+// Launches, restores or resumes the catalog app, passing it the (already encoded) parameters.
+pub fn launch_app(app: &AppCatalogEntry, param_list: Vec<String>) -> Result<LaunchTiming, DabError> {
+    launch_app_since(app, param_list, Instant::now())
+}
 
-    if let Some(mut parameters) = _dab_request.parameters.clone() {
-        if parameters.len() > 0 {
-            if is_cobalt {
-                // Decode each parameter before appending to the list
-                for param in &mut parameters {
-                    *param = decode(param).unwrap().to_string();
-                }
-            }
-            param_list.append(&mut parameters);
-        }
+// Timings are measured from `started`, so a relaunch includes stopping the running app.
+fn launch_app_since(app: &AppCatalogEntry, param_list: Vec<String>, started: Instant) -> Result<LaunchTiming, DabError> {
+    let callsign = app.callsign();
+
+    if get_app_lifecycle_backend() == AppLifecycleBackend::AppManager {
+        let app_created = matches!(get_app_state(&callsign)?, AppState::Stopped);
+        app_manager::launch_app(&callsign, &app.join_parameters(&param_list))?;
+        let accepted = elapsed_ms(started);
+        let (foreground, focused) = wait_till_app_starts(app, app_created, started)?;
+        return Ok(launch_timing(app_created, accepted, foreground, focused));
     }
 
+    let launch_req_params = RDKShellRequestParams {
+        callsign: callsign.clone(),
+    };
+
     let mut app_created = true;
-    let mut app_state = get_app_state(&_dab_request.appId)?;
+    let mut app_state = get_app_state(&callsign)?;
+
+    // Restoring or starting the app needs memory; make room first when a launch policy is set.
+    if matches!(app_state, AppState::Stopped | AppState::Hibernated) {
+        launch_policy::make_room_for(&callsign);
+    }
 
     if let AppState::Hibernated = app_state {
-        println!("Restoring {} from hibernation", _dab_request.appId);
+        println!("Restoring {} from hibernation", callsign);
 
         // App is hibernated; restore app.
         let request = RdkRequest {
@@ -74,63 +106,41 @@ pub fn process(_dab_request: LaunchApplicationRequest) -> Result<String, DabErro
 
         let json_string = serde_json::to_string(&request).unwrap();
         http_post(json_string)?;
-        app_state = get_app_state(&_dab_request.appId)?;
+        app_state = wait_till_app_restores(&callsign)?;
     }
 
     match app_state {
         AppState::Stopped => {
             // Cold launch of app.
-            let req_params = if is_cobalt {
-                let url = format!("https://www.youtube.com/tv?{}", param_list.join("&"));
-                let language = get_rdk_language().map_err(|err|{
-                    eprintln!("Unable to retrieve RDK language.");
-                    err
-                })?;
-                let config = json!({"url": url, "language": language});
-                RDKShellParams {
-                    callsign: _dab_request.appId.clone(),
-                    r#type: "Cobalt".into(),
-                    configuration: Some(config.to_string()),
-                }
-            } else if is_netflix {
-                let querystring = format!("{}", param_list.join("&"));
-                let config = json!({"querystring": querystring});
-                RDKShellParams {
-                    callsign: _dab_request.appId.clone(),
-                    r#type: "Netflix".into(),
-                    configuration: Some(config.to_string()),
-                }
-            } else {
-                RDKShellParams {
-                    callsign: _dab_request.appId.clone(),
-                    r#type: "LightningApp".into(),
-                    configuration: None,
-                }
-            };
-            send_rdkshell_launch_request(req_params)?;
+            let configuration = app
+                .configuration
+                .as_ref()
+                .map(|config| app.expand_value(config, &param_list).map(|c| c.to_string()))
+                .transpose()?;
+            let uri = app
+                .uri
+                .as_ref()
+                .map(|uri| app.expand(uri, &param_list))
+                .transpose()?;
+
+            send_rdkshell_launch_request(RDKShellParams {
+                callsign: callsign.clone(),
+                r#type: app.rdkshell_type.clone(),
+                uri,
+                configuration,
+            })?;
         },
         AppState::Suspended | AppState::Invisible | AppState::Visible => {
             app_created = false;
             //// FIXME: If parameters(?) are App startup specific, it may not take effect when resuming "plugin" runtime.
             // Deeplink is required only if you need to pass parameters to the app runtime.
-            if param_list.len() > 0 {
+            if !param_list.is_empty() {
                 // Do app specific deeplinking.
-                if is_cobalt {
-                    // Cobalt plugin specific.
-                    let request = RdkRequest {
-                        jsonrpc: "2.0".into(),
-                        id: 3,
-                        method: _dab_request.appId.clone() + ".1.deeplink".into(),
-                        params: format!("https://www.youtube.com/tv?{}", param_list.join("&")),
-                    };
-
-                    let json_string = serde_json::to_string(&request).unwrap();
-                    http_post(json_string)?;
-                } else {
-                    // Other App specific deeplinking.
-                    return Err(DabError::Err500(
-                        "Require App specific deeplinking implementation.".to_string(),
-                    ));
+                if let DeeplinkStrategy::Relaunch = send_deeplink(app, &param_list)? {
+                    println!("Relaunching {} to pass the launch parameters", callsign);
+                    rdkshell_destroy(callsign.clone())?;
+                    wait_till_app_stops(&callsign)?;
+                    return launch_app_since(app, param_list, started);
                 }
             }
 
@@ -145,15 +155,70 @@ pub fn process(_dab_request: LaunchApplicationRequest) -> Result<String, DabErro
             let json_string = serde_json::to_string(&request).unwrap();
             http_post(json_string)?;
         },
-        _ => {
-            println!("Should not reach here in any condition. Invalid {} App state: {:?}",
-                _dab_request.appId.clone(), app_state);
+        AppState::Hibernated => {
+            return Err(DabError::Err500(
+                format!("{} is still hibernated after the restore request.", callsign),
+            ));
         }
     }
 
-    wait_till_app_starts(_dab_request.appId, app_created)?;
+    let accepted = elapsed_ms(started);
+    let (foreground, focused) = wait_till_app_starts(app, app_created, started)?;
+    Ok(launch_timing(app_created, accepted, foreground, focused))
+}
 
-    Ok("{}".to_string())
+fn launch_timing(app_created: bool, accepted: u64, foreground: u64, focused: u64) -> LaunchTiming {
+    LaunchTiming {
+        launchType: if app_created { "cold" } else { "resume" }.to_string(),
+        requestAcceptedMs: accepted,
+        foregroundMs: foreground,
+        focusedMs: focused,
+    }
+}
+
+pub fn elapsed_ms(started: Instant) -> u64 {
+    started.elapsed().as_millis() as u64
+}
+
+// Publishes the timing of a launch or exit when enabled in the settings.
+pub fn publish_lifecycle_metrics<T: Serialize>(app_id: &str, operation: &str, timing: &T) {
+    if !get_lifecycle_metrics_enabled() {
+        return;
+    }
+
+    let payload = json!({
+        "timestamp": SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+        "appId": app_id,
+        "operation": operation,
+        "timing": timing,
+    });
+    publish("applications/metrics", payload.to_string());
+}
+
+// Passes the parameters to the running app using the first catalog deeplink strategy that works.
+pub fn send_deeplink<'a>(app: &'a AppCatalogEntry, param_list: &[String]) -> Result<&'a DeeplinkStrategy, DabError> {
+    let mut result = Err(DabError::Err500(
+        "Require App specific deeplinking implementation.".to_string(),
+    ));
+
+    for strategy in app.deeplink.iter() {
+        match strategy {
+            DeeplinkStrategy::Jsonrpc { method, params } => {
+                let method = app.expand(method, param_list)?;
+                let params = app.expand_value(params, param_list)?;
+                match rdk_request_with_params::<Value, Value>(&method, params) {
+                    Ok(_) => return Ok(strategy),
+                    Err(err) => {
+                        println!("Deeplink with {} failed; trying the next strategy.", method);
+                        result = Err(err);
+                    }
+                }
+            },
+            DeeplinkStrategy::Relaunch => return Ok(strategy),
+        }
+    }
+
+    result
 }
 
 //******************************* Generic Implementation for Reuse *******************************/
@@ -162,6 +227,8 @@ pub fn process(_dab_request: LaunchApplicationRequest) -> Result<String, DabErro
 pub struct RDKShellParams {
     pub callsign: String,
     pub r#type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
     pub configuration: Option<String>,
 }
 
@@ -209,8 +276,20 @@ pub fn send_rdkshell_launch_request(params: RDKShellParams) -> Result<(), DabErr
 }
 
 pub fn move_to_front_set_focus(callsign: String) -> Result<String, DabError> {
-    //****************org.rdk.RDKShell.moveToFront/setFocus******************************//
+    move_to_front(&callsign)?;
+    set_focus(&callsign)?;
+    Ok("{}".to_string())
+}
+
+pub fn move_to_front(callsign: &str) -> Result<(), DabError> {
+    rdkshell_client_request("org.rdk.RDKShell.moveToFront", callsign)
+}
 
+pub fn set_focus(callsign: &str) -> Result<(), DabError> {
+    rdkshell_client_request("org.rdk.RDKShell.setFocus", callsign)
+}
+
+fn rdkshell_client_request(method: &str, callsign: &str) -> Result<(), DabError> {
     // RDK Request Common Structs
     #[derive(Serialize, Clone)]
     struct RequestParams {
@@ -226,28 +305,29 @@ pub fn move_to_front_set_focus(callsign: String) -> Result<String, DabError> {
         params: RequestParams,
     }
 
-    let req_params = RequestParams {
-        client: callsign.clone(),
-        callsign: callsign.clone(),
-    };
     let request = RdkRequest {
         jsonrpc: "2.0".into(),
         id: 3,
-        method: "org.rdk.RDKShell.moveToFront".into(),
-        params: req_params.clone(),
+        method: method.into(),
+        params: RequestParams {
+            client: callsign.to_string(),
+            callsign: callsign.to_string(),
+        },
     };
     let json_string = serde_json::to_string(&request).unwrap();
     http_post(json_string)?;
+    Ok(())
+}
 
-    let request = RdkRequest {
-        jsonrpc: "2.0".into(),
-        id: 3,
-        method: "org.rdk.RDKShell.setFocus".into(),
-        params: req_params.clone(),
-    };
-    let json_string = serde_json::to_string(&request).unwrap();
-    http_post(json_string)?;
-    Ok("{}".to_string())
+// Returns the RDKShell clients, front most first.
+pub fn get_z_order() -> Result<Vec<String>, DabError> {
+    #[derive(Deserialize)]
+    struct GetZOrderResult {
+        clients: Vec<String>,
+    }
+
+    let rdkresponse: RdkResponse<GetZOrderResult> = rdk_request("org.rdk.RDKShell.getZOrder")?;
+    Ok(rdkresponse.result.clients)
 }
 
 pub fn set_visibility(client: String, visible: bool) -> Result<String, DabError> {
@@ -363,7 +443,17 @@ pub fn rdkshell_suspend(callsign:String) -> Result<String, DabError> {
     Ok("{}".to_string())
 }
 
+pub fn rdkshell_hibernate(callsign: String) -> Result<(), DabError> {
+    let _rdkresponse: Value = rdk_request_with_params(
+        "org.rdk.RDKShell.hibernate",
+        json!({ "callsign": callsign }),
+    )?;
+    Ok(())
+}
+
 pub fn rdkshell_destroy(callsign:String) -> Result<String, DabError> {
+    crash_monitor::expect_stop(&callsign);
+
     #[derive(Serialize)]
     struct RdkRequest {
         jsonrpc: String,
@@ -391,20 +481,93 @@ pub fn rdkshell_destroy(callsign:String) -> Result<String, DabError> {
     Ok("{}".to_string())
 }
 
-pub fn wait_till_app_starts(req_params: String, app_created: bool) -> Result<(), DabError> {
+// Returns the milliseconds from `started` to the app reaching the foreground and getting focus.
+pub fn wait_till_app_starts(app: &AppCatalogEntry, app_created: bool, started: Instant) -> Result<(u64, u64), DabError> {
+    let callsign = app.callsign();
+    let timeout_type = if !app_created {
+        "resume_launch_timeout_ms"
+    } else {
+        "cold_launch_timeout_ms"
+    };
+    let lifecycle_timeout = get_lifecycle_timeout(&app.app_id.to_lowercase(), timeout_type);
+
+    // With lifecycle events the launch completes on the transition; the lifecycle timeout is the
+    // upper bound, but never less than the time given to polling.
+    let wait_time = time::Duration::from_millis(lifecycle_timeout.max(LAUNCH_MIN_WAIT_MS));
+    let foreground = match state_cache::wait_for_state(&callsign, wait_time, |state| state == RuntimeState::Running) {
+        Some(true) => elapsed_ms(started),
+        Some(false) => {
+            return Err(DabError::Err500(
+                format!("{} did not start within {}ms.", callsign, wait_time.as_millis()),
+            ));
+        },
+        None => poll_till_app_starts(&callsign, lifecycle_timeout, started)?,
+    };
+
+    // AppManager brings the app to the front and focuses it as part of the launch.
+    if get_app_lifecycle_backend() == AppLifecycleBackend::AppManager {
+        return Ok((foreground, foreground));
+    }
+
+    if !get_visibility(callsign.clone())? {
+        set_visibility(callsign.clone(), true)?;
+    }
+    move_to_front_set_focus(callsign.clone())?;
+
+    Ok((foreground, elapsed_ms(started)))
+}
+
+pub fn wait_till_app_stops(callsign: &str) -> Result<(), DabError> {
+    let wait_time = time::Duration::from_millis(LAUNCH_MIN_WAIT_MS);
+    let stopped = match state_cache::wait_for_state(callsign, wait_time, |state| state == RuntimeState::Stopped) {
+        Some(stopped) => stopped,
+        None => {
+            let mut stopped = false;
+            for _idx in 1..=20 {
+                if let AppState::Stopped = get_app_state(callsign)? {
+                    stopped = true;
+                    break;
+                }
+                thread::sleep(time::Duration::from_millis(250));
+            }
+            stopped
+        }
+    };
+
+    if !stopped {
+        return Err(DabError::Err500(
+            format!("{} did not stop within {}ms.", callsign, wait_time.as_millis()),
+        ));
+    }
+    Ok(())
+}
+
+// Returns the state the app is in once it has left hibernation, or Hibernated if it didn't in time.
+fn wait_till_app_restores(callsign: &str) -> Result<AppState, DabError> {
+    let wait_time = time::Duration::from_millis(LAUNCH_MIN_WAIT_MS);
+    if state_cache::wait_for_state(callsign, wait_time, |state| state != RuntimeState::Hibernated).is_none() {
+        for _idx in 1..=20 {
+            let app_state = get_app_state(callsign)?;
+            if !matches!(app_state, AppState::Hibernated) {
+                return Ok(app_state);
+            }
+            thread::sleep(time::Duration::from_millis(250));
+        }
+    }
+    get_app_state(callsign)
+}
+
+// Returns the milliseconds from `started` to the app reaching the foreground; the lifecycle
+// sleep that follows is not included.
+fn poll_till_app_starts(callsign: &str, lifecycle_timeout: u64, started: Instant) -> Result<u64, DabError> {
     let mut app_state: String = "STOPPED".to_string();
+    let mut foreground = 0;
     for _idx in 1..=20 {
         thread::sleep(time::Duration::from_millis(250));
-        app_state = get_dab_app_state(req_params.clone())?;
+        app_state = get_dab_app_state(callsign.to_string())?;
         if app_state == "FOREGROUND".to_string() {
-            let timeout_type = if !app_created {
-                "resume_launch_timeout_ms"
-            } else {
-                "cold_launch_timeout_ms"
-            };
-
-            let sleep_time = get_lifecycle_timeout(&req_params.to_lowercase(), timeout_type).unwrap_or(2500);
-            std::thread::sleep(time::Duration::from_millis(sleep_time));
+            foreground = elapsed_ms(started);
+            std::thread::sleep(time::Duration::from_millis(lifecycle_timeout));
             break;
         }
     }
@@ -415,10 +578,5 @@ pub fn wait_till_app_starts(req_params: String, app_created: bool) -> Result<(),
         ));
     }
 
-    if !get_visibility(req_params.clone())? {
-        set_visibility(req_params.clone(), true)?;
-    }
-    move_to_front_set_focus(req_params.clone())?;
-
-    Ok(())
-}
\ No newline at end of file
+    Ok(foreground)
+}
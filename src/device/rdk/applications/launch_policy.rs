@@ -0,0 +1,122 @@
+use crate::dab::structs::DabError;
+use crate::device::rdk::applications::catalog::BackgroundExitPolicy;
+use crate::device::rdk::applications::exit::{destroy_app, hibernate_app, suspend_app};
+use crate::device::rdk::applications::get_state::get_rdkshell_runtimes;
+use crate::device::rdk::applications::get_state::AppState;
+use crate::device::rdk::applications::launch::{get_z_order, EXIT_MIN_WAIT_MS};
+use crate::device::rdk::applications::state_cache;
+use crate::device::rdk::applications::state_cache::RuntimeState;
+use crate::device::rdk::input::key::is_system_client;
+use crate::device::rdk::interface::get_device_free_memory;
+use crate::device::rdk::interface::{get_launch_policy, LaunchPolicySettings};
+use std::{thread, time};
+
+// Before launching `callsign`, applies the configured action to the least recently used
+// background apps until both the background app budget and the free memory budget are met.
+// Every background app counts against the budget, whatever its state; apps already in the state
+// the action leads to are left as they are. The policy is best effort; failures are logged and
+// the launch goes ahead.
+pub fn make_room_for(callsign: &str) {
+    let Some(policy) = get_launch_policy() else {
+        return;
+    };
+
+    let background_apps = match get_background_apps(callsign, &policy) {
+        Ok(background_apps) => background_apps,
+        Err(err) => {
+            println!("Launch policy: failed to list background apps: {:?}", err);
+            return;
+        }
+    };
+
+    // The oldest apps beyond the budget; only the most recent ones may stay as they are.
+    let excess = policy
+        .max_background_apps
+        .map_or(0, |max| background_apps.len().saturating_sub(max));
+
+    for (index, (client, state)) in background_apps.into_iter().enumerate() {
+        let over_budget = index < excess;
+        let low_memory = policy.min_free_memory_kb.is_some_and(|min| {
+            get_device_free_memory().is_ok_and(|free| free < min)
+        });
+        if !over_budget && !low_memory {
+            break;
+        }
+        if !action_applies(policy.action, state) {
+            continue;
+        }
+
+        println!("Launch policy: applying {:?} to {} before launching {}", policy.action, client, callsign);
+        if let Err(err) = apply_action(&client, state, policy.action) {
+            println!("Launch policy: failed on {}: {:?}", client, err);
+        }
+    }
+}
+
+// Returns the background apps the policy manages, least recently used first. RDKShell keeps the
+// last launched app in front, so apps are ordered by the Z order; apps without a display are the
+// oldest.
+fn get_background_apps(callsign: &str, policy: &LaunchPolicySettings) -> Result<Vec<(String, RuntimeState)>, DabError> {
+    let z_order = get_z_order()?;
+    let position = |client: &str| {
+        z_order
+            .iter()
+            .position(|c| c.eq_ignore_ascii_case(client))
+            .unwrap_or(usize::MAX)
+    };
+
+    let mut background_apps: Vec<(String, RuntimeState)> = get_rdkshell_runtimes()?
+        .into_iter()
+        .filter(|runtime| !runtime.callsign.eq_ignore_ascii_case(callsign))
+        .filter(|runtime| !is_system_client(&runtime.callsign))
+        .filter(|runtime| {
+            !policy
+                .protected_clients
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(&runtime.callsign))
+        })
+        .filter_map(|runtime| runtime.runtime_state().map(|state| (runtime.callsign, state)))
+        .collect();
+
+    background_apps.sort_by_key(|(client, _)| std::cmp::Reverse(position(client)));
+    Ok(background_apps)
+}
+
+// Whether the action still changes an app in the given state.
+fn action_applies(action: BackgroundExitPolicy, state: RuntimeState) -> bool {
+    match action {
+        BackgroundExitPolicy::Suspend => state == RuntimeState::Running,
+        BackgroundExitPolicy::Hibernate => state != RuntimeState::Hibernated,
+        BackgroundExitPolicy::Destroy => true,
+    }
+}
+
+fn apply_action(client: &str, state: RuntimeState, action: BackgroundExitPolicy) -> Result<(), DabError> {
+    let app_state = if state == RuntimeState::Suspended {
+        AppState::Suspended
+    } else {
+        AppState::Invisible
+    };
+
+    let target = match action {
+        BackgroundExitPolicy::Suspend => {
+            suspend_app(client)?;
+            RuntimeState::Suspended
+        },
+        BackgroundExitPolicy::Hibernate => {
+            hibernate_app(client, &app_state)?;
+            RuntimeState::Hibernated
+        },
+        BackgroundExitPolicy::Destroy => {
+            destroy_app(client)?;
+            RuntimeState::Stopped
+        },
+    };
+
+    // Give the platform time to release the memory before it is measured again.
+    let wait_time = time::Duration::from_millis(EXIT_MIN_WAIT_MS);
+    if state_cache::wait_for_state(client, wait_time, |state| state == target).is_none() {
+        thread::sleep(wait_time);
+    }
+    Ok(())
+}
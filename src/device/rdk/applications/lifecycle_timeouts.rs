@@ -0,0 +1,65 @@
+use crate::dab::structs::AppLifecycleTimeouts;
+use crate::dab::structs::DabError;
+use crate::dab::structs::LifecycleTimeout;
+use crate::dab::structs::LifecycleTimeoutsRequest;
+use crate::dab::structs::LifecycleTimeoutsResponse;
+use crate::device::rdk::applications::catalog::get_catalog;
+use crate::device::rdk::interface::{get_default_lifecycle_timeout, get_lifecycle_timeout_with_source};
+use crate::device::rdk::interface::{get_lifecycle_configured_apps, LifecycleTimeoutSource};
+use crate::device::rdk::interface::LIFECYCLE_TIMEOUT_TYPES;
+
+#[allow(non_snake_case)]
+#[allow(dead_code)]
+#[allow(unused_mut)]
+pub fn process(_dab_request: LifecycleTimeoutsRequest) -> Result<String, DabError> {
+    let mut ResponseOperator = LifecycleTimeoutsResponse::default();
+    // *** Fill in the fields of the struct LifecycleTimeoutsResponse here ***
+
+    // Either the requested app, or every app of the catalog and of the platform file.
+    let mut app_ids = match _dab_request.appId {
+        Some(app_id) if !app_id.is_empty() => vec![app_id],
+        _ => {
+            let mut app_ids: Vec<String> = get_catalog().into_iter().map(|app| app.app_id).collect();
+            for app_id in get_lifecycle_configured_apps() {
+                if !app_ids.iter().any(|a| a.eq_ignore_ascii_case(&app_id)) {
+                    app_ids.push(app_id);
+                }
+            }
+            app_ids.sort();
+            app_ids
+        }
+    };
+
+    for app_id in app_ids.drain(..) {
+        let timeouts = LIFECYCLE_TIMEOUT_TYPES
+            .iter()
+            .map(|timeout_type| {
+                let (value, source) = get_lifecycle_timeout_with_source(&app_id, timeout_type);
+                lifecycle_timeout(timeout_type, value, source)
+            })
+            .collect();
+        ResponseOperator.apps.push(AppLifecycleTimeouts {
+            appId: app_id,
+            timeouts,
+        });
+    }
+
+    ResponseOperator.defaults = LIFECYCLE_TIMEOUT_TYPES
+        .iter()
+        .map(|timeout_type| {
+            let (value, source) = get_default_lifecycle_timeout(timeout_type);
+            lifecycle_timeout(timeout_type, value, source)
+        })
+        .collect();
+
+    // *******************************************************************
+    Ok(serde_json::to_string(&ResponseOperator).unwrap())
+}
+
+fn lifecycle_timeout(timeout_type: &str, value: u64, source: LifecycleTimeoutSource) -> LifecycleTimeout {
+    LifecycleTimeout {
+        name: timeout_type.to_string(),
+        valueMs: value,
+        source: source.as_str().to_string(),
+    }
+}
@@ -2,7 +2,11 @@ use crate::dab::structs::Application;
 use crate::dab::structs::ApplicationListRequest;
 use crate::dab::structs::DabError;
 use crate::dab::structs::ListApplicationsResponse;
+use crate::device::rdk::applications::app_manager;
+use crate::device::rdk::applications::catalog::{get_app, get_catalog};
+use crate::device::rdk::applications::packages::{get_installed_packages, PackageInfo};
 use crate::device::rdk::interface::rdk_request;
+use crate::device::rdk::interface::{get_app_lifecycle_backend, AppLifecycleBackend};
 use crate::device::rdk::interface::RdkResponse;
 use serde::Deserialize;
 
@@ -13,38 +17,77 @@ pub fn process(_dab_request: ApplicationListRequest) -> Result<String, DabError>
     let mut ResponseOperator = ListApplicationsResponse::default();
     // *** Fill in the fields of the struct Application here ***
 
-    #[derive(Deserialize)]
-    struct GetAvailableTypesResult {
-        types: Vec<String>,
-        success: bool,
-    }
+    let mut app_ids = vec![];
+    let running;
 
-    let rdkresponse: RdkResponse<GetAvailableTypesResult> =
-        rdk_request("org.rdk.RDKShell.getAvailableTypes")?;
-    for s in rdkresponse.result.types.iter() {
-        match s.as_str() {
-            "YouTube" => {
-                let app = Application {
-                    appId: ("YouTube").to_string(),
-                };
-                ResponseOperator.applications.push(app);
-            }
-            "Amazon" => {
-                let app = Application {
-                    appId: ("PrimeVideo").to_string(),
-                };
-                ResponseOperator.applications.push(app);
-            }
-            "Netflix" => {
-                let app = Application {
-                    appId: ("Netflix").to_string(),
-                };
-                ResponseOperator.applications.push(app);
+    if get_app_lifecycle_backend() == AppLifecycleBackend::AppManager {
+        app_ids = app_manager::get_installed_apps()?;
+        running = app_manager::get_loaded_app_ids().unwrap_or_default();
+    } else {
+        #[derive(Deserialize)]
+        struct GetAvailableTypesResult {
+            types: Vec<String>,
+            success: bool,
+        }
+
+        let rdkresponse: RdkResponse<GetAvailableTypesResult> =
+            rdk_request("org.rdk.RDKShell.getAvailableTypes")?;
+        for app in get_catalog() {
+            if rdkresponse.result.types.iter().any(|t| t == app.available_type()) {
+                app_ids.push(app.app_id);
             }
-            &_ => {},
         }
+        running = get_rdkshell_clients().unwrap_or_default();
+    }
+
+    let packages = get_installed_packages();
+    for app_id in app_ids {
+        ResponseOperator.applications.push(describe_app(&app_id, &packages, &running));
     }
 
     // *******************************************************************
     Ok(serde_json::to_string(&ResponseOperator).unwrap())
 }
+
+// Fills in the app metadata from the catalog, the installed packages and the running apps.
+// The friendly name and version are DAB 2.1 fields.
+#[allow(unused_mut)]
+fn describe_app(app_id: &str, packages: &[PackageInfo], running: &[String]) -> Application {
+    let app = get_app(app_id);
+    let callsign = app.callsign();
+    let package = packages
+        .iter()
+        .find(|p| p.id.eq_ignore_ascii_case(app_id) || p.id.eq_ignore_ascii_case(&callsign));
+
+    let mut application = Application {
+        appId: app_id.to_string(),
+        iconUrl: app.icon_url.clone(),
+        installSource: Some(match package {
+            Some(package) => package.installer.as_str().to_string(),
+            None => "preinstalled".to_string(),
+        }),
+        running: Some(running.iter().any(|r| r.eq_ignore_ascii_case(&callsign))),
+        ..Default::default()
+    };
+
+    #[cfg(feature = "2_1")]
+    {
+        application.friendlyName = app
+            .friendly_name
+            .clone()
+            .or(package.and_then(|p| p.name.clone()));
+        application.version = package.and_then(|p| p.version.clone());
+    }
+
+    application
+}
+
+fn get_rdkshell_clients() -> Result<Vec<String>, DabError> {
+    #[derive(Deserialize)]
+    struct GetClientsResult {
+        clients: Vec<String>,
+    }
+
+    let rdkresponse: RdkResponse<GetClientsResult> = rdk_request("org.rdk.RDKShell.getClients")?;
+    Ok(rdkresponse.result.clients)
+}
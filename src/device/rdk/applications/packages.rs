@@ -0,0 +1,502 @@
+// Apps installed through a Thunder package manager: org.rdk.PackageManager on RDK-E images, LISA
+// on RDK-V images with DAC support.
+
+use crate::dab::notify;
+use crate::dab::structs::DabError;
+use crate::dab::structs::NotificationLevel;
+use crate::device::rdk::interface::thunder_plugin_exists;
+use crate::device::rdk::interface::{rdk_request, rdk_request_with_params};
+use crate::device::rdk::interface::{ws_close, ws_open, ws_receive_event, ws_send};
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio::runtime::Runtime;
+use tokio::time::timeout;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+// Package type given to LISA for the packages installed by the adapter.
+const LISA_PACKAGE_TYPE: &str = "application/vnd.rdk-app.dac.native";
+const EVENT_DESIGNATOR: &str = "dab.packages";
+// Install and uninstall wait at most this long for the package manager to finish.
+const PACKAGE_OPERATION_TIMEOUT_S: u64 = 600;
+// Progress is published on dab/<device-id>/messages at this interval.
+#[cfg(not(test))]
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+#[cfg(test)]
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Installer {
+    PackageManager,
+    LISA,
+}
+
+impl Installer {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Installer::PackageManager => "PackageManager",
+            Installer::LISA => "LISA",
+        }
+    }
+}
+
+// Returns the package manager apps are installed with, preferring org.rdk.PackageManager.
+pub fn get_installer() -> Result<Installer, DabError> {
+    if thunder_plugin_exists("org.rdk.PackageManager") {
+        Ok(Installer::PackageManager)
+    } else if thunder_plugin_exists("LISA") {
+        Ok(Installer::LISA)
+    } else {
+        Err(DabError::Err501(
+            "No package manager (org.rdk.PackageManager or LISA) on this device".to_string(),
+        ))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PackageInfo {
+    pub id: String,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub package_type: Option<String>,
+    // Plugin the package was installed with.
+    pub installer: Installer,
+}
+
+fn string_field(value: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|key| value[*key].as_str())
+        .find(|s| !s.is_empty())
+        .map(|s| s.to_string())
+}
+
+fn get_package_manager_packages() -> Result<Vec<PackageInfo>, DabError> {
+    let response: Value = rdk_request("org.rdk.PackageManager.listPackages")?;
+    let packages = match &response["result"]["packages"] {
+        Value::Array(list) => list.clone(),
+        _ => match &response["result"] {
+            Value::Array(list) => list.clone(),
+            _ => vec![],
+        },
+    };
+
+    Ok(packages
+        .iter()
+        .filter_map(|package| {
+            Some(PackageInfo {
+                id: string_field(package, &["packageId", "id"])?,
+                name: string_field(package, &["name", "appName"]),
+                version: string_field(package, &["version"]),
+                package_type: None,
+                installer: Installer::PackageManager,
+            })
+        })
+        .collect())
+}
+
+fn get_lisa_packages() -> Result<Vec<PackageInfo>, DabError> {
+    let response: Value = rdk_request_with_params("LISA.1.getList", json!({}))?;
+    let apps = match &response["result"]["apps"] {
+        Value::Array(list) => list.clone(),
+        _ => vec![],
+    };
+
+    // Each app lists its installed versions; report the last one.
+    Ok(apps
+        .iter()
+        .filter_map(|app| {
+            let installed = app["installed"].as_array().and_then(|list| list.last());
+            Some(PackageInfo {
+                id: string_field(app, &["id"])?,
+                name: installed.and_then(|i| string_field(i, &["appName"])),
+                version: installed.and_then(|i| string_field(i, &["version"])),
+                package_type: string_field(app, &["type"]),
+                installer: Installer::LISA,
+            })
+        })
+        .collect())
+}
+
+// Returns the installed packages of every package manager present on the device. Package
+// managers that don't answer are skipped.
+pub fn get_installed_packages() -> Vec<PackageInfo> {
+    let mut packages = vec![];
+
+    if thunder_plugin_exists("org.rdk.PackageManager") {
+        match get_package_manager_packages() {
+            Ok(mut list) => packages.append(&mut list),
+            Err(_) => println!("Unable to list org.rdk.PackageManager packages."),
+        }
+    }
+
+    if thunder_plugin_exists("LISA") {
+        match get_lisa_packages() {
+            Ok(mut list) => packages.append(&mut list),
+            Err(_) => println!("Unable to list LISA packages."),
+        }
+    }
+
+    packages
+}
+
+// Packages given as "file://<path>" or as an absolute path are installed from the device.
+fn local_package_path(url: &str) -> Option<String> {
+    if let Some(path) = url.strip_prefix("file://") {
+        Some(path.to_string())
+    } else if url.starts_with('/') {
+        Some(url.to_string())
+    } else {
+        None
+    }
+}
+
+pub fn install_package(app_id: &str, version: &str, url: &str) -> Result<(), DabError> {
+    let rt = Runtime::new().unwrap();
+    match get_installer()? {
+        Installer::PackageManager => {
+            let file_locator = match local_package_path(url) {
+                Some(path) => path,
+                None => rt.block_on(package_manager_download(app_id, url))?,
+            };
+            let _rdkresponse: Value = rdk_request_with_params(
+                "org.rdk.PackageManager.install",
+                json!({
+                    "packageId": app_id,
+                    "version": version,
+                    "additionalMetadata": [],
+                    "fileLocator": file_locator
+                }),
+            )?;
+            Ok(())
+        }
+        Installer::LISA => {
+            let url = match local_package_path(url) {
+                Some(path) => format!("file://{}", path),
+                None => url.to_string(),
+            };
+            rt.block_on(run_lisa_operation(
+                "LISA.1.install",
+                json!({
+                    "type": LISA_PACKAGE_TYPE,
+                    "id": app_id,
+                    "version": version,
+                    "url": url,
+                    "appName": app_id,
+                    "category": ""
+                }),
+                &format!("Installing {}", app_id),
+            ))
+        }
+    }
+}
+
+pub fn uninstall_package(app_id: &str) -> Result<(), DabError> {
+    get_installer()?;
+
+    let package = get_installed_packages()
+        .into_iter()
+        .find(|p| p.id == app_id)
+        .ok_or(DabError::Err400(format!("{} is not installed", app_id)))?;
+
+    // The package is removed by the package manager that reported it.
+    match package.installer {
+        Installer::PackageManager => {
+            let _rdkresponse: Value = rdk_request_with_params(
+                "org.rdk.PackageManager.uninstall",
+                json!({ "packageId": app_id }),
+            )?;
+            Ok(())
+        }
+        Installer::LISA => {
+            let rt = Runtime::new().unwrap();
+            rt.block_on(run_lisa_operation(
+                "LISA.1.uninstall",
+                json!({
+                    "type": package.package_type.unwrap_or(LISA_PACKAGE_TYPE.to_string()),
+                    "id": app_id,
+                    "version": package.version.unwrap_or_default(),
+                    "uninstallType": "full"
+                }),
+                &format!("Uninstalling {}", app_id),
+            ))
+        }
+    }
+}
+
+async fn subscribe(ws_stream: &mut WsStream, plugin: &str, event: &str) -> Result<(), DabError> {
+    let payload = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": format!("{}.register", plugin),
+        "params": {
+            "event": event,
+            "id": EVENT_DESIGNATOR
+        }
+    });
+    ws_send(ws_stream, payload).await
+}
+
+// Calls a LISA method that answers with an operation handle, then waits for the
+// onOperationStatus event that completes the operation.
+async fn run_lisa_operation(method: &str, params: Value, description: &str) -> Result<(), DabError> {
+    let mut ws_stream = ws_open().await?;
+    subscribe(&mut ws_stream, "LISA.1", "onOperationStatus").await?;
+
+    let result = async {
+        let rdkresponse: Value = rdk_request_with_params(method, params)?;
+        let handle = rdkresponse["result"]
+            .as_str()
+            .or(rdkresponse["result"]["handle"].as_str())
+            .ok_or(DabError::Err500(format!("{} returned no handle", method)))?
+            .to_string();
+
+        let deadline = Instant::now() + Duration::from_secs(PACKAGE_OPERATION_TIMEOUT_S);
+        while Instant::now() < deadline {
+            match timeout(PROGRESS_INTERVAL, ws_receive_event(&mut ws_stream)).await {
+                Ok(message) => {
+                    let message = message?;
+                    let params = &message["params"];
+                    if !message["method"].as_str().unwrap_or("").ends_with("onOperationStatus")
+                        || params["handle"].as_str() != Some(handle.as_str())
+                    {
+                        continue;
+                    }
+                    match params["status"].as_str().unwrap_or("") {
+                        "Success" => return Ok(()),
+                        "Failed" => {
+                            return Err(DabError::Err500(format!(
+                                "{} failed: {}",
+                                description,
+                                params["details"].as_str().unwrap_or("")
+                            )))
+                        }
+                        status => notify(NotificationLevel::info, &format!("{}: {}", description, status)),
+                    }
+                }
+                Err(_) => {
+                    if let Ok(progress) = rdk_request_with_params::<_, Value>(
+                        "LISA.1.getProgress",
+                        json!({ "handle": handle }),
+                    ) {
+                        if let Some(percent) = progress["result"].as_u64() {
+                            notify(NotificationLevel::info, &format!("{}: {}%", description, percent));
+                        }
+                    }
+                }
+            }
+        }
+
+        Err(DabError::Err500(format!("{} timed out", description)))
+    }
+    .await;
+
+    let _ = ws_close(&mut ws_stream).await;
+    result
+}
+
+// Downloads the package with org.rdk.PackageManager and returns its location on the device.
+async fn package_manager_download(app_id: &str, url: &str) -> Result<String, DabError> {
+    let description = format!("Downloading {}", app_id);
+    let mut ws_stream = ws_open().await?;
+    subscribe(&mut ws_stream, "org.rdk.PackageManager.1", "onAppDownloadStatus").await?;
+
+    let result = async {
+        let rdkresponse: Value =
+            rdk_request_with_params("org.rdk.PackageManager.download", json!({ "url": url }))?;
+        let download_id = rdkresponse["result"]["downloadId"]
+            .as_str()
+            .or(rdkresponse["result"].as_str())
+            .ok_or(DabError::Err500("org.rdk.PackageManager.download returned no downloadId".to_string()))?
+            .to_string();
+
+        let deadline = Instant::now() + Duration::from_secs(PACKAGE_OPERATION_TIMEOUT_S);
+        while Instant::now() < deadline {
+            match timeout(PROGRESS_INTERVAL, ws_receive_event(&mut ws_stream)).await {
+                Ok(message) => {
+                    let message = message?;
+                    if !message["method"].as_str().unwrap_or("").ends_with("onAppDownloadStatus") {
+                        continue;
+                    }
+                    // The status list comes either as JSON or JSON encoded in a string.
+                    let statuses = match &message["params"]["jsonresponse"] {
+                        Value::String(encoded) => serde_json::from_str::<Value>(encoded).unwrap_or(Value::Null),
+                        other => other.clone(),
+                    };
+                    let Some(status) = statuses
+                        .as_array()
+                        .and_then(|list| list.iter().find(|s| s["downloadId"].as_str() == Some(download_id.as_str())))
+                    else {
+                        continue;
+                    };
+
+                    let fail_reason = status["failReason"].as_str().unwrap_or("");
+                    if !fail_reason.is_empty() && fail_reason != "NONE" {
+                        return Err(DabError::Err500(format!("{} failed: {}", description, fail_reason)));
+                    }
+                    if let Some(file_locator) = status["fileLocator"].as_str() {
+                        return Ok(file_locator.to_string());
+                    }
+                }
+                Err(_) => {
+                    if let Ok(progress) = rdk_request_with_params::<_, Value>(
+                        "org.rdk.PackageManager.progress",
+                        json!({ "downloadId": download_id }),
+                    ) {
+                        if let Some(percent) = progress["result"]["percent"].as_u64() {
+                            notify(NotificationLevel::info, &format!("{}: {}%", description, percent));
+                        }
+                    }
+                }
+            }
+        }
+
+        Err(DabError::Err500(format!("{} timed out", description)))
+    }
+    .await;
+
+    let _ = ws_close(&mut ws_stream).await;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::rdk::mock_thunder::MockThunder;
+
+    fn lisa_package(id: &str, version: &str) -> Value {
+        json!({
+            "id": id,
+            "type": LISA_PACKAGE_TYPE,
+            "installed": [{ "version": version, "appName": id }]
+        })
+    }
+
+    #[test]
+    fn package_manager_install_downloads_with_progress() {
+        let thunder = MockThunder::start(&["org.rdk.PackageManager"], |method, _, events| match method {
+            "org.rdk.PackageManager.download" => {
+                let statuses = json!([{
+                    "downloadId": "d1",
+                    "fileLocator": "/opt/CDL/my-app.tar",
+                    "failReason": "NONE"
+                }]);
+                events.send_after(500, "onAppDownloadStatus", json!({ "jsonresponse": statuses.to_string() }));
+                Ok(json!({ "downloadId": "d1" }))
+            }
+            "org.rdk.PackageManager.progress" => Ok(json!({ "percent": 50 })),
+            "org.rdk.PackageManager.install" => Ok(json!("i1")),
+            _ => Err("Unknown method".to_string()),
+        });
+
+        install_package("my-app", "1.2.0", "https://apps.example.com/my-app.tar").unwrap();
+
+        assert!(!thunder.calls("org.rdk.PackageManager.progress").is_empty());
+        let install = thunder.calls("org.rdk.PackageManager.install");
+        assert_eq!(install.len(), 1);
+        assert_eq!(install[0]["packageId"], "my-app");
+        assert_eq!(install[0]["version"], "1.2.0");
+        assert_eq!(install[0]["fileLocator"], "/opt/CDL/my-app.tar");
+    }
+
+    #[test]
+    fn package_manager_install_reports_download_failure() {
+        let _thunder = MockThunder::start(&["org.rdk.PackageManager"], |method, _, events| match method {
+            "org.rdk.PackageManager.download" => {
+                let statuses = json!([{ "downloadId": "d1", "failReason": "DOWNLOAD_FAILURE" }]);
+                events.send_after(200, "onAppDownloadStatus", json!({ "jsonresponse": statuses }));
+                Ok(json!({ "downloadId": "d1" }))
+            }
+            "org.rdk.PackageManager.progress" => Ok(json!({ "percent": 10 })),
+            _ => Err("Unknown method".to_string()),
+        });
+
+        let result = install_package("my-app", "1.2.0", "https://apps.example.com/my-app.tar");
+        assert!(matches!(result, Err(DabError::Err500(msg)) if msg.contains("DOWNLOAD_FAILURE")));
+    }
+
+    #[test]
+    fn lisa_install_waits_for_the_operation() {
+        let thunder = MockThunder::start(&["LISA"], |method, _, events| match method {
+            "LISA.1.install" => {
+                events.send_after(200, "onOperationStatus", json!({ "handle": "h1", "status": "Progress" }));
+                events.send_after(400, "onOperationStatus", json!({ "handle": "h1", "status": "Success" }));
+                Ok(json!("h1"))
+            }
+            "LISA.1.getProgress" => Ok(json!(50)),
+            _ => Err("Unknown method".to_string()),
+        });
+
+        install_package("my-app", "1.2.0", "/tmp/my-app.tar").unwrap();
+
+        let install = thunder.calls("LISA.1.install");
+        assert_eq!(install.len(), 1);
+        assert_eq!(install[0]["id"], "my-app");
+        assert_eq!(install[0]["type"], LISA_PACKAGE_TYPE);
+        assert_eq!(install[0]["url"], "file:///tmp/my-app.tar");
+        assert!(thunder.calls("org.rdk.PackageManager.install").is_empty());
+    }
+
+    #[test]
+    fn lisa_install_reports_failure() {
+        let _thunder = MockThunder::start(&["LISA"], |method, _, events| match method {
+            "LISA.1.install" => {
+                events.send_after(200, "onOperationStatus", json!({ "handle": "h1", "status": "Failed", "details": "Bad package" }));
+                Ok(json!("h1"))
+            }
+            _ => Err("Unknown method".to_string()),
+        });
+
+        let result = install_package("my-app", "1.2.0", "https://apps.example.com/my-app.tar");
+        assert!(matches!(result, Err(DabError::Err500(msg)) if msg.contains("Bad package")));
+    }
+
+    #[test]
+    fn uninstall_goes_through_the_package_manager_that_reported_the_package() {
+        let thunder = MockThunder::start(&["org.rdk.PackageManager", "LISA"], |method, _, events| match method {
+            "org.rdk.PackageManager.listPackages" => Ok(json!({ "packages": [{ "packageId": "other-app" }] })),
+            "LISA.1.getList" => Ok(json!({ "apps": [lisa_package("my-app", "2.0.0")] })),
+            "LISA.1.uninstall" => {
+                events.send_after(200, "onOperationStatus", json!({ "handle": "h2", "status": "Success" }));
+                Ok(json!("h2"))
+            }
+            "org.rdk.PackageManager.uninstall" => Ok(json!({})),
+            _ => Err("Unknown method".to_string()),
+        });
+
+        uninstall_package("my-app").unwrap();
+
+        let uninstall = thunder.calls("LISA.1.uninstall");
+        assert_eq!(uninstall.len(), 1);
+        assert_eq!(uninstall[0]["id"], "my-app");
+        assert_eq!(uninstall[0]["version"], "2.0.0");
+        assert!(thunder.calls("org.rdk.PackageManager.uninstall").is_empty());
+
+        uninstall_package("other-app").unwrap();
+        let uninstall = thunder.calls("org.rdk.PackageManager.uninstall");
+        assert_eq!(uninstall.len(), 1);
+        assert_eq!(uninstall[0]["packageId"], "other-app");
+    }
+
+    #[test]
+    fn uninstall_rejects_unknown_packages() {
+        let _thunder = MockThunder::start(&["LISA"], |method, _, _| match method {
+            "LISA.1.getList" => Ok(json!({ "apps": [lisa_package("my-app", "2.0.0")] })),
+            _ => Err("Unknown method".to_string()),
+        });
+
+        assert!(matches!(uninstall_package("missing-app"), Err(DabError::Err400(_))));
+    }
+
+    #[test]
+    fn no_package_manager_is_not_implemented() {
+        let thunder = MockThunder::start(&["org.rdk.RDKShell"], |_, _, _| Err("Unknown method".to_string()));
+
+        let result = install_package("my-app", "1.2.0", "https://apps.example.com/my-app.tar");
+        assert!(matches!(result, Err(DabError::Err501(_))));
+        assert!(matches!(uninstall_package("my-app"), Err(DabError::Err501(_))));
+        assert!(get_installed_packages().is_empty());
+        assert_eq!(thunder.calls("Controller.1.status").len(), 1);
+    }
+}
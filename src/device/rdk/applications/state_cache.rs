@@ -0,0 +1,224 @@
+// Live application state, kept up to date from RDKShell lifecycle events so that get-state,
+// launch and exit don't have to poll Thunder. Until the event subscription is up, and on the
+// AppManager backend, callers fall back to querying Thunder.
+
+use crate::dab::structs::DabError;
+use crate::device::rdk::applications::crash_monitor;
+use crate::device::rdk::applications::get_state::get_rdkshell_runtimes;
+use crate::device::rdk::interface::{get_app_lifecycle_backend, AppLifecycleBackend};
+use crate::device::rdk::interface::{ws_close, ws_open, ws_receive_event, ws_send};
+use lazy_static::lazy_static;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Condvar, Mutex};
+use std::{thread, time};
+use tokio::runtime::Runtime;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RuntimeState {
+    Stopped,
+    Hibernated,
+    Suspended,
+    Running,
+}
+
+#[derive(Default)]
+struct Cache {
+    live: bool,
+    // Keyed by lowercase callsign; apps missing from the map are stopped.
+    apps: HashMap<String, RuntimeState>,
+}
+
+impl Cache {
+    fn state_of(&self, key: &str) -> RuntimeState {
+        self.apps.get(key).copied().unwrap_or(RuntimeState::Stopped)
+    }
+}
+
+lazy_static! {
+    static ref STATE_CACHE: (Mutex<Cache>, Condvar) = (Mutex::new(Cache::default()), Condvar::new());
+}
+
+// RDKShell events and the state the app is in after each of them. Not every RDKShell release
+// sends every event.
+const RDKSHELL_EVENTS: &[(&str, RuntimeState)] = &[
+    ("onLaunched", RuntimeState::Running),
+    ("onApplicationLaunched", RuntimeState::Running),
+    ("onApplicationActivated", RuntimeState::Running),
+    ("onApplicationResumed", RuntimeState::Running),
+    ("onSuspended", RuntimeState::Suspended),
+    ("onPluginSuspended", RuntimeState::Suspended),
+    ("onApplicationSuspended", RuntimeState::Suspended),
+    ("onHibernated", RuntimeState::Hibernated),
+    // Restored apps go back to the suspended state they were hibernated from.
+    ("onRestored", RuntimeState::Suspended),
+    ("onDestroyed", RuntimeState::Stopped),
+    ("onApplicationTerminated", RuntimeState::Stopped),
+];
+
+const EVENT_DESIGNATOR: &str = "dab.appstate";
+// Plugins deactivated or restarted by the Monitor plugin, e.g. after a crash.
+const MONITOR_DESIGNATOR: &str = "dab.monitor";
+// Plugin state changes with their reason, which tells crashes from requested stops.
+const CONTROLLER_DESIGNATOR: &str = "dab.controller";
+const RECONNECT_DELAY_MS: u64 = 5000;
+
+// Starts following RDKShell events in the background. The connection is retried until Thunder
+// answers, and again whenever it drops.
+pub fn start() {
+    thread::spawn(|| {
+        let rt = Runtime::new().unwrap();
+        loop {
+            match rt.block_on(follow_events()) {
+                Ok(()) => return,
+                Err(_) => {
+                    set_live(false);
+                    thread::sleep(time::Duration::from_millis(RECONNECT_DELAY_MS));
+                }
+            }
+        }
+    });
+}
+
+// Returns Ok when there is nothing to follow (AppManager backend), Err when the connection is lost.
+async fn follow_events() -> Result<(), DabError> {
+    let mut ws_stream = ws_open().await?;
+
+    if get_app_lifecycle_backend() == AppLifecycleBackend::AppManager {
+        ws_close(&mut ws_stream).await?;
+        return Ok(());
+    }
+
+    for (id, (event, _)) in RDKSHELL_EVENTS.iter().enumerate() {
+        let payload = json!({
+            "jsonrpc": "2.0",
+            "id": id + 1,
+            "method": "org.rdk.RDKShell.1.register",
+            "params": {
+                "event": event,
+                "id": EVENT_DESIGNATOR
+            }
+        });
+        ws_send(&mut ws_stream, payload).await?;
+    }
+    // Fails quietly on images without the Monitor plugin.
+    let payload = json!({
+        "jsonrpc": "2.0",
+        "id": RDKSHELL_EVENTS.len() + 1,
+        "method": "Monitor.1.register",
+        "params": {
+            "event": "action",
+            "id": MONITOR_DESIGNATOR
+        }
+    });
+    ws_send(&mut ws_stream, payload).await?;
+    let payload = json!({
+        "jsonrpc": "2.0",
+        "id": RDKSHELL_EVENTS.len() + 2,
+        "method": "Controller.1.register",
+        "params": {
+            "event": "statechange",
+            "id": CONTROLLER_DESIGNATOR
+        }
+    });
+    ws_send(&mut ws_stream, payload).await?;
+
+    // Seed only after subscribing, so that no transition is missed.
+    let mut apps = HashMap::new();
+    for runtime in get_rdkshell_runtimes()? {
+        let state = runtime.runtime_state().unwrap_or(RuntimeState::Running);
+        apps.insert(runtime.callsign.to_lowercase(), state);
+    }
+    {
+        let (lock, cvar) = &*STATE_CACHE;
+        let mut cache = lock.lock().unwrap();
+        cache.apps = apps;
+        cache.live = true;
+        cvar.notify_all();
+    }
+    println!("Following RDKShell application lifecycle events.");
+
+    loop {
+        let message = ws_receive_event(&mut ws_stream).await?;
+        handle_event(&message);
+    }
+}
+
+fn handle_event(message: &Value) {
+    // Registration responses carry no method.
+    let Some(method) = message["method"].as_str() else {
+        return;
+    };
+    if method.starts_with(MONITOR_DESIGNATOR) {
+        let callsign = message["params"]["callsign"].as_str().unwrap_or_default();
+        crash_monitor::monitor_action(&message["params"], get_state(callsign));
+        return;
+    }
+    if method.starts_with(CONTROLLER_DESIGNATOR) {
+        let callsign = message["params"]["callsign"].as_str().unwrap_or_default();
+        crash_monitor::plugin_state_changed(&message["params"], get_state(callsign));
+        return;
+    }
+    let event = method.rsplit('.').next().unwrap_or(method);
+    let Some((_, state)) = RDKSHELL_EVENTS.iter().find(|(e, _)| *e == event) else {
+        return;
+    };
+    let Some(client) = message["params"]["client"]
+        .as_str()
+        .or(message["params"]["callsign"].as_str())
+    else {
+        return;
+    };
+
+    let (lock, cvar) = &*STATE_CACHE;
+    let mut cache = lock.lock().unwrap();
+    let last_state = cache.state_of(&client.to_lowercase());
+    if *state == RuntimeState::Stopped {
+        cache.apps.remove(&client.to_lowercase());
+    } else {
+        cache.apps.insert(client.to_lowercase(), *state);
+    }
+    cvar.notify_all();
+    drop(cache);
+
+    if *state == RuntimeState::Stopped && last_state != RuntimeState::Stopped {
+        crash_monitor::app_stopped(client, last_state);
+    }
+}
+
+fn set_live(live: bool) {
+    let (lock, cvar) = &*STATE_CACHE;
+    lock.lock().unwrap().live = live;
+    cvar.notify_all();
+}
+
+// Returns the state of the app, or None when the cache isn't following events.
+pub fn get_state(callsign: &str) -> Option<RuntimeState> {
+    let cache = STATE_CACHE.0.lock().unwrap();
+    if !cache.live {
+        return None;
+    }
+    Some(cache.state_of(&callsign.to_lowercase()))
+}
+
+// Waits up to `timeout` for the app to reach a state accepted by `done`. Returns whether it did,
+// or None when the cache isn't following events and the caller has to poll instead.
+pub fn wait_for_state<F>(callsign: &str, timeout: time::Duration, done: F) -> Option<bool>
+where
+    F: Fn(RuntimeState) -> bool,
+{
+    let key = callsign.to_lowercase();
+    let (lock, cvar) = &*STATE_CACHE;
+    let cache = lock.lock().unwrap();
+    if !cache.live {
+        return None;
+    }
+
+    let (cache, _) = cvar
+        .wait_timeout_while(cache, timeout, |cache| cache.live && !done(cache.state_of(&key)))
+        .unwrap();
+    if !cache.live {
+        return None;
+    }
+    Some(done(cache.state_of(&key)))
+}
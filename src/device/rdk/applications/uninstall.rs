@@ -0,0 +1,26 @@
+use crate::dab::notify;
+use crate::dab::structs::DabError;
+use crate::dab::structs::NotificationLevel;
+use crate::dab::structs::UninstallApplicationRequest;
+use crate::dab::structs::UninstallApplicationResponse;
+use crate::device::rdk::applications::packages::uninstall_package;
+
+#[allow(non_snake_case)]
+#[allow(dead_code)]
+#[allow(unused_mut)]
+pub fn process(_dab_request: UninstallApplicationRequest) -> Result<String, DabError> {
+    let mut ResponseOperator = UninstallApplicationResponse::default();
+    // *** Fill in the fields of the struct UninstallApplicationResponse here ***
+
+    if _dab_request.appId.is_empty() {
+        return Err(DabError::Err400(
+            "request missing 'appId' parameter".to_string(),
+        ));
+    }
+
+    uninstall_package(&_dab_request.appId)?;
+    notify(NotificationLevel::info, &format!("{} uninstalled", _dab_request.appId));
+
+    // *******************************************************************
+    Ok(serde_json::to_string(&ResponseOperator).unwrap())
+}
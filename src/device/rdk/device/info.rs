@@ -8,6 +8,7 @@ use crate::dab::structs::NetworkInterface;
 use crate::dab::structs::NetworkInterfaceType;
 use crate::device::rdk::interface::get_device_id;
 use crate::device::rdk::interface::http_post;
+use crate::device::rdk::interface::thunder_method;
 use crate::device::rdk::interface::{get_rdk_device_info, get_thunder_property};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -30,7 +31,7 @@ pub fn process(_dab_request: DeviceInfoRequest) -> Result<String, DabError> {
     let request = GetConnectedVideoDisplaysRequest {
         jsonrpc: "2.0".into(),
         id: 3,
-        method: "org.rdk.DisplaySettings.getConnectedVideoDisplays".into(),
+        method: thunder_method("org.rdk.DisplaySettings.getConnectedVideoDisplays"),
     };
 
     #[derive(Deserialize)]
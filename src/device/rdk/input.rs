@@ -1,3 +1,6 @@
 pub mod key;
+pub mod key_capture;
 pub mod key_press;
+pub mod key_sequence;
 pub mod long_key_press;
+pub mod text;
@@ -1,39 +1,39 @@
 pub mod list;
 
-use crate::device::rdk::interface::rdk_request;
-use crate::device::rdk::interface::RdkResponse;
-use serde::Deserialize;
+use crate::dab::structs::DabError;
+use crate::device::rdk::applications::catalog::get_app;
+use crate::device::rdk::applications::get_state::get_app_state;
+use crate::device::rdk::applications::get_state::AppState;
+use crate::device::rdk::applications::launch::get_z_order;
+use crate::device::rdk::interface::{get_key_injection, KeyInjection};
 
 // Keys that need to be sent directly to the focused app to bypass
-// platform-level key intercepts.
+// platform-level key intercepts, as set by the keymap.
 pub(crate) fn needs_direct_injection(key_code: &str) -> bool {
-    matches!(key_code, "KEY_VOLUME_UP" | "KEY_VOLUME_DOWN" | "KEY_MUTE")
+    get_key_injection(key_code) == KeyInjection::GenerateKey
 }
 
-fn is_system_client(client: &str) -> bool {
+pub(crate) fn is_system_client(client: &str) -> bool {
     client.starts_with("subtec_")
         || client.starts_with("test-")
         || client == "rdkshell_display"
 }
 
 pub(crate) fn get_focused_client() -> Option<String> {
-    #[derive(Deserialize)]
-    struct GetZOrderResult {
-        clients: Vec<String>,
-        success: bool,
-    }
-
-    rdk_request::<RdkResponse<GetZOrderResult>>("org.rdk.RDKShell.1.getZOrder")
+    get_z_order()
         .ok()
-        .and_then(|response| {
-            if !response.result.success {
-                return None;
-            }
+        .and_then(|clients| clients.into_iter().find(|c| !is_system_client(c)))
+}
 
-            response
-                .result
-                .clients
-                .into_iter()
-                .find(|c| !is_system_client(c))
-        })
+// Returns the RDKShell client of the app keys are sent to, which must be running.
+pub(crate) fn get_target_client(app_id: Option<&str>) -> Result<Option<String>, DabError> {
+    let Some(app_id) = app_id.filter(|app_id| !app_id.is_empty()) else {
+        return Ok(None);
+    };
+
+    let callsign = get_app(app_id).callsign();
+    if let AppState::Stopped = get_app_state(&callsign)? {
+        return Err(DabError::Err400(format!("{} is not running.", app_id)));
+    }
+    Ok(Some(callsign))
 }
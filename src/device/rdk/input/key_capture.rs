@@ -0,0 +1,179 @@
+// Publishes the keys pressed on the device to dab/<device-id>/input/events, so that test sessions
+// can be recorded and replayed with input/key-press. Keys are followed through RDKShell key
+// listeners on the clients running when the capture starts.
+
+use crate::dab::publish;
+use crate::dab::structs::DabError;
+use crate::dab::structs::KeyCaptureStartRequest;
+use crate::dab::structs::KeyCaptureStartResponse;
+use crate::dab::structs::KeyCaptureStopRequest;
+use crate::dab::structs::KeyCaptureStopResponse;
+use crate::device::rdk::applications::launch::get_z_order;
+use crate::device::rdk::input::key::is_system_client;
+use crate::device::rdk::interface::{get_key_name, get_keycode, get_rdk_keys};
+use crate::device::rdk::interface::{ws_close, ws_open, ws_receive_event, ws_send};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::runtime::Runtime;
+use tokio::time::timeout;
+
+const EVENT_DESIGNATOR: &str = "dab.keycapture";
+// How often the capture thread checks whether it was stopped.
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+static CAPTURING: AtomicBool = AtomicBool::new(false);
+static CAPTURE_THREAD: Mutex<Option<thread::JoinHandle<()>>> = Mutex::new(None);
+
+#[allow(non_snake_case)]
+#[allow(dead_code)]
+#[allow(unused_mut)]
+pub fn start(_dab_request: KeyCaptureStartRequest) -> Result<String, DabError> {
+    let mut ResponseOperator = KeyCaptureStartResponse::default();
+    // *** Fill in the fields of the struct KeyCaptureStartResponse here ***
+
+    let mut capture_thread = CAPTURE_THREAD.lock().unwrap();
+    if CAPTURING.load(Ordering::SeqCst) {
+        return Err(DabError::Err400("Key capture is already running".to_string()));
+    }
+    // A capture that ended by itself, e.g. when Thunder went away, is cleaned up first.
+    if let Some(handle) = capture_thread.take() {
+        let _ = handle.join();
+    }
+
+    let clients: Vec<String> = get_z_order()?
+        .into_iter()
+        .filter(|client| !is_system_client(client))
+        .collect();
+    if clients.is_empty() {
+        return Err(DabError::Err500("No RDKShell client to listen to".to_string()));
+    }
+    ResponseOperator.clients = clients.clone();
+
+    CAPTURING.store(true, Ordering::SeqCst);
+    *capture_thread = Some(thread::spawn(move || {
+        let rt = Runtime::new().unwrap();
+        if let Err(err) = rt.block_on(capture(&clients)) {
+            println!("Key capture ended: {:?}", err);
+        }
+        CAPTURING.store(false, Ordering::SeqCst);
+    }));
+
+    // *******************************************************************
+    Ok(serde_json::to_string(&ResponseOperator).unwrap())
+}
+
+#[allow(non_snake_case)]
+#[allow(dead_code)]
+#[allow(unused_mut)]
+pub fn stop(_dab_request: KeyCaptureStopRequest) -> Result<String, DabError> {
+    let mut ResponseOperator = KeyCaptureStopResponse::default();
+    // *** Fill in the fields of the struct KeyCaptureStopResponse here ***
+
+    let mut capture_thread = CAPTURE_THREAD.lock().unwrap();
+    let Some(handle) = capture_thread.take() else {
+        return Err(DabError::Err400("Key capture is not running".to_string()));
+    };
+    CAPTURING.store(false, Ordering::SeqCst);
+    let _ = handle.join();
+
+    // *******************************************************************
+    Ok(serde_json::to_string(&ResponseOperator).unwrap())
+}
+
+async fn capture(clients: &[String]) -> Result<(), DabError> {
+    let mut ws_stream = ws_open().await?;
+
+    let payload = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "org.rdk.RDKShell.1.register",
+        "params": {
+            "event": "onKeyEvent",
+            "id": EVENT_DESIGNATOR
+        }
+    });
+    ws_send(&mut ws_stream, payload).await?;
+    // Listen without taking the keys from the apps.
+    for (id, client) in clients.iter().enumerate() {
+        ws_send(&mut ws_stream, key_listener_request("addKeyListener", client, id + 2, true)).await?;
+    }
+    println!("Capturing the keys of {:?}.", clients);
+
+    let mut result = Ok(());
+    while CAPTURING.load(Ordering::SeqCst) {
+        match timeout(STOP_CHECK_INTERVAL, ws_receive_event(&mut ws_stream)).await {
+            Ok(Ok(message)) => handle_event(&message),
+            Ok(Err(err)) => {
+                result = Err(err);
+                break;
+            }
+            Err(_) => {}
+        }
+    }
+
+    if result.is_ok() {
+        for (id, client) in clients.iter().enumerate() {
+            ws_send(&mut ws_stream, key_listener_request("removeKeyListener", client, id + 2, false)).await?;
+        }
+        ws_close(&mut ws_stream).await?;
+    }
+    result
+}
+
+// Listens to every mapped key.
+fn key_listener_request(method: &str, client: &str, id: usize, add: bool) -> Value {
+    let keys: Vec<Value> = get_rdk_keys()
+        .iter()
+        .filter_map(|key| get_keycode(key.clone()))
+        .map(|code| {
+            if add {
+                json!({ "keyCode": code, "modifiers": [], "activate": false, "propagate": true })
+            } else {
+                json!({ "keyCode": code, "modifiers": [] })
+            }
+        })
+        .collect();
+
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": format!("org.rdk.RDKShell.1.{}", method),
+        "params": {
+            "client": client,
+            "keys": keys
+        }
+    })
+}
+
+// Publishes {"timestampMs", "keyCode", "rdkKeyCode", "pressed", "modifiers", "client"}. Keys missing
+// from the keymap are published with a null keyCode.
+fn handle_event(message: &Value) {
+    let Some(method) = message["method"].as_str() else {
+        return;
+    };
+    if !method.starts_with(EVENT_DESIGNATOR) {
+        return;
+    }
+
+    let params = &message["params"];
+    let Some(rdk_key_code) = params["keyCode"].as_u64().or(params["keycode"].as_u64()) else {
+        return;
+    };
+    let pressed = params["keyDown"]
+        .as_bool()
+        .or(params["pressed"].as_bool())
+        .unwrap_or_else(|| params["type"].as_str() != Some("keyUp"));
+
+    let payload = json!({
+        "timestampMs": SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64,
+        "keyCode": get_key_name(rdk_key_code as u16),
+        "rdkKeyCode": rdk_key_code,
+        "pressed": pressed,
+        "modifiers": params["modifiers"].as_array().cloned().unwrap_or_default(),
+        "client": params["client"],
+    });
+    publish("input/events", payload.to_string());
+}
@@ -2,8 +2,9 @@ use crate::dab::structs::DabError;
 use crate::dab::structs::KeyPressRequest;
 use crate::dab::structs::KeyPressResponse;
 use crate::device::rdk::interface::get_keycode;
-use crate::device::rdk::interface::http_post;
-use crate::device::rdk::input::key::{get_focused_client, needs_direct_injection};
+use crate::device::rdk::interface::rdk_request_with_params;
+use crate::device::rdk::interface::RdkResponseSimple;
+use crate::device::rdk::input::key::{get_focused_client, get_target_client, needs_direct_injection};
 use crate::device::rdk::system::settings::get::{get_rdk_audio_volume, get_rdk_mute};
 use crate::device::rdk::system::settings::set::{set_rdk_audio_volume, set_rdk_mute};
 use serde::Serialize;
@@ -20,8 +21,18 @@ pub fn process(_dab_request: KeyPressRequest) -> Result<String, DabError> {
         ));
     }
 
+    let client = get_target_client(_dab_request.appId.as_deref())?;
+    press_key(&_dab_request.keyCode, &[], client.as_deref())?;
+
+    Ok(serde_json::to_string(&ResponseOperator).unwrap())
+}
+
+// Presses and releases the key, with the RDKShell modifiers ("ctrl", "alt", "shift") held. Keys
+// for a given client are sent to it instead of the focused one.
+#[allow(non_snake_case)]
+pub fn press_key(key_code: &str, modifiers: &[String], client: Option<&str>) -> Result<(), DabError> {
     // Handle volume keys via DisplaySettings API to ensure volume actually works
-    match _dab_request.keyCode.as_str() {
+    match key_code {
         "KEY_VOLUME_UP" => {
             let current = get_rdk_audio_volume().unwrap_or(50);
             let new_volume = (current + VOLUME_STEP).min(100);
@@ -40,24 +51,17 @@ pub fn process(_dab_request: KeyPressRequest) -> Result<String, DabError> {
         _ => {}
     }
 
-    let KeyCode: u16 = match get_keycode(_dab_request.keyCode.clone()) {
+    let KeyCode: u16 = match get_keycode(key_code.to_string()) {
         Some(k) => *k,
         None => return Err(DabError::Err400("keyCode not found".to_string())),
     };
 
     // For volume keys and other intercepted keys, use generateKey with client parameter
-    // to bypass platform-level key intercepts. Send to the currently focused app.
-    // If no focused app found, skip key injection (volume still changes via DisplaySettings).
-    if needs_direct_injection(&_dab_request.keyCode) {
-        if let Some(focused_client) = get_focused_client() {
-            #[derive(Serialize)]
-            struct GenerateKeyRequest {
-                jsonrpc: String,
-                id: i32,
-                method: String,
-                params: GenerateKeyParams,
-            }
-
+    // to bypass platform-level key intercepts. Send to the requested app, else to the currently
+    // focused app. If no focused app found, skip key injection (volume still changes via DisplaySettings).
+    if client.is_some() || needs_direct_injection(key_code) {
+        let target_client = client.map(str::to_string).or_else(get_focused_client);
+        if let Some(focused_client) = target_client {
             #[derive(Serialize)]
             struct GenerateKeyParams {
                 keys: Vec<KeyEntry>,
@@ -73,7 +77,7 @@ pub fn process(_dab_request: KeyPressRequest) -> Result<String, DabError> {
 
             let key_entry = KeyEntry {
                 keyCode: KeyCode,
-                modifiers: vec![],
+                modifiers: modifiers.to_vec(),
                 delay: 0.0,
                 client: focused_client,
             };
@@ -82,49 +86,33 @@ pub fn process(_dab_request: KeyPressRequest) -> Result<String, DabError> {
                 keys: vec![key_entry],
             };
 
-            let request = GenerateKeyRequest {
-                jsonrpc: "2.0".into(),
-                id: 3,
-                method: "org.rdk.RDKShell.1.generateKey".into(),
-                params: req_params,
-            };
-
-            let json_string = serde_json::to_string(&request).unwrap();
-            http_post(json_string)?;
+            let _rdkresponse: RdkResponseSimple =
+                rdk_request_with_params("org.rdk.RDKShell.generateKey", req_params)?;
         }
 
-        return Ok(serde_json::to_string(&ResponseOperator).unwrap());
+        return Ok(());
     }
 
     // Use injectKey for all other keys
-    #[derive(Serialize)]
-    struct InjectKeyRequest {
-        jsonrpc: String,
-        id: i32,
-        method: String,
-        params: InjectKeyRequestParams,
-    }
+    inject_key(KeyCode, modifiers)
+}
 
+// Injects the RDK key code with org.rdk.RDKShell.injectKey.
+pub fn inject_key(key_code: u16, modifiers: &[String]) -> Result<(), DabError> {
     #[derive(Serialize)]
+    #[allow(non_snake_case)]
     struct InjectKeyRequestParams {
         keyCode: u16,
         modifiers: Vec<String>,
     }
 
     let req_params = InjectKeyRequestParams {
-        keyCode: KeyCode,
-        modifiers: vec![],
-    };
-
-    let request = InjectKeyRequest {
-        jsonrpc: "2.0".into(),
-        id: 3,
-        method: "org.rdk.RDKShell.1.injectKey".into(),
-        params: req_params,
+        keyCode: key_code,
+        modifiers: modifiers.to_vec(),
     };
 
-    let json_string = serde_json::to_string(&request).unwrap();
-    http_post(json_string)?;
+    let _rdkresponse: RdkResponseSimple =
+        rdk_request_with_params("org.rdk.RDKShell.injectKey", req_params)?;
 
-    Ok(serde_json::to_string(&ResponseOperator).unwrap())
+    Ok(())
 }
@@ -0,0 +1,81 @@
+use crate::dab::structs::DabError;
+use crate::dab::structs::KeySequenceRequest;
+use crate::dab::structs::KeySequenceResponse;
+use crate::device::rdk::input::key_press::press_key;
+use crate::device::rdk::input::long_key_press::{hold_key, validate_hold_key};
+use crate::device::rdk::interface::get_keycode;
+use std::thread;
+use std::time::Duration;
+
+const MAX_STEPS: usize = 200;
+const RDKSHELL_MODIFIERS: [&str; 3] = ["ctrl", "alt", "shift"];
+
+#[allow(non_snake_case)]
+#[allow(dead_code)]
+#[allow(unused_mut)]
+pub fn process(_dab_request: KeySequenceRequest) -> Result<String, DabError> {
+    let mut ResponseOperator = KeySequenceResponse::default();
+    // *** Fill in the fields of the struct KeySequenceResponse here ***
+
+    if _dab_request.keys.is_empty() {
+        return Err(DabError::Err400(
+            "request missing 'keys' parameter".to_string(),
+        ));
+    }
+
+    if _dab_request.keys.len() > MAX_STEPS {
+        return Err(DabError::Err400(
+            format!("'keys' has more than {} steps", MAX_STEPS),
+        ));
+    }
+
+    // Reject malformed sequences before any key is sent.
+    for (index, step) in _dab_request.keys.iter().enumerate() {
+        if get_keycode(step.keyCode.clone()).is_none() {
+            return Err(DabError::Err400(
+                format!("step {}: keyCode '{}' not found", index, step.keyCode),
+            ));
+        }
+        if step.durationMs.unwrap_or(0) > 0 {
+            validate_hold_key(&step.keyCode).map_err(|err| step_error(index, &step.keyCode, err))?;
+        }
+        for modifier in step.modifiers.iter().flatten() {
+            if !RDKSHELL_MODIFIERS.contains(&modifier.as_str()) {
+                return Err(DabError::Err400(
+                    format!("step {}: modifier '{}' is not one of {:?}", index, modifier, RDKSHELL_MODIFIERS),
+                ));
+            }
+        }
+    }
+
+    for (index, step) in _dab_request.keys.iter().enumerate() {
+        let modifiers = step.modifiers.clone().unwrap_or_default();
+        let result = match step.durationMs {
+            Some(duration) if duration > 0 => hold_key(&step.keyCode, duration as u64, &modifiers, None),
+            _ => press_key(&step.keyCode, &modifiers, None),
+        };
+
+        if let Err(err) = result {
+            return Err(step_error(index, &step.keyCode, err));
+        }
+        ResponseOperator.completedSteps = index + 1;
+
+        if let Some(delay) = step.delayMs {
+            thread::sleep(Duration::from_millis(delay as u64));
+        }
+    }
+
+    // *******************************************************************
+    Ok(serde_json::to_string(&ResponseOperator).unwrap())
+}
+
+// Keeps the status of the failure, telling which step failed. Steps are counted from 0, so the
+// index is also the number of steps completed before it.
+fn step_error(index: usize, key_code: &str, err: DabError) -> DabError {
+    let describe = |message: String| format!("step {} ({}) failed: {}", index, key_code, message);
+    match err {
+        DabError::Err400(message) => DabError::Err400(describe(message)),
+        DabError::Err500(message) => DabError::Err500(describe(message)),
+        DabError::Err501(message) => DabError::Err501(describe(message)),
+    }
+}
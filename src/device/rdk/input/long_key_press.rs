@@ -2,9 +2,13 @@ use crate::dab::structs::DabError;
 use crate::dab::structs::LongKeyPressRequest;
 use crate::dab::structs::LongKeyPressResponse;
 use crate::device::rdk::interface::get_keycode;
-use crate::device::rdk::interface::http_post;
-use crate::device::rdk::input::key::{get_focused_client, needs_direct_injection};
+use crate::device::rdk::interface::thunder_plugin_exists;
+use crate::device::rdk::interface::{get_key_hold_settings, KeyHoldSettings};
+use crate::device::rdk::interface::rdk_request_with_params;
+use crate::device::rdk::interface::RdkResponseSimple;
+use crate::device::rdk::input::key::{get_focused_client, get_target_client, needs_direct_injection};
 use serde::Serialize;
+use serde_json::{json, Value};
 use serde_json;
 use std::thread;
 use std::time::Duration;
@@ -29,29 +33,53 @@ pub fn process(_dab_request: LongKeyPressRequest) -> Result<String, DabError> {
         ));
     }
 
-    let mut KeyCode: u16;
+    let client = get_target_client(_dab_request.appId.as_deref())?;
+    hold_key(&_dab_request.keyCode, _dab_request.durationMs as u64, &[], client.as_deref())?;
 
-    match _dab_request.keyCode.as_str() {
-        "KEY_FAST_FORWARD" => return Err(DabError::Err400("'KEY_FAST_FORWARD' not supported".to_string())),
-        _ => {}
+    // *******************************************************************
+    Ok(serde_json::to_string(&ResponseOperator).unwrap())
+}
+
+// Returns the RDK key code of a key that can be held, so that callers can check keys before
+// sending any.
+pub fn validate_hold_key(key_code: &str) -> Result<u16, DabError> {
+    if key_code == "KEY_FAST_FORWARD" {
+        return Err(DabError::Err400("'KEY_FAST_FORWARD' not supported".to_string()));
     }
 
-    match get_keycode(_dab_request.keyCode.clone()) {
-        Some(k) => KeyCode = *k,
-        None => return Err(DabError::Err400("keyCode' not found".to_string())),
+    match get_keycode(key_code.to_string()) {
+        Some(k) => Ok(*k),
+        None => Err(DabError::Err400("keyCode' not found".to_string())),
     }
+}
+
+// Holds the key down for `total_time` milliseconds, with the RDKShell modifiers held, and returns
+// once it is released. Keys for a given client are sent to it instead of the focused one.
+#[allow(non_snake_case)]
+#[allow(unused_mut)]
+pub fn hold_key(key_code: &str, total_time: u64, modifiers: &[String], client: Option<&str>) -> Result<(), DabError> {
+    let KeyCode = validate_hold_key(key_code)?;
 
-    let total_time: u64 = _dab_request.durationMs as u64;
+    let start = Instant::now();
 
-    //#########org.rdk.RDKShell.generateKey#########
-    #[derive(Serialize)]
-    struct GenerateKeyRequest {
-        jsonrpc: String,
-        id: i32,
-        method: String,
-        params: GenerateKeyRequestParams,
+    // Like a real remote: one key down, repeats while held and one key up. Keys sent to the
+    // focused or a given client, and platforms without RemoteControl, use RDKShell.generateKey instead.
+    if client.is_none() && !needs_direct_injection(key_code) {
+        let settings = get_key_hold_settings();
+        if settings.remote_control && thunder_plugin_exists("RemoteControl") {
+            match remote_control_key("press", &settings, KeyCode, modifiers) {
+                Ok(()) => {
+                    let deadline = start + Duration::from_millis(total_time);
+                    return remote_control_hold(&settings, KeyCode, modifiers, deadline);
+                }
+                Err(err) => {
+                    println!("RemoteControl.press failed: {:?}; using RDKShell.generateKey.", err);
+                }
+            }
+        }
     }
 
+    //#########org.rdk.RDKShell.generateKey#########
     #[derive(Serialize)]
     struct GenerateKeyRequestParams {
         keys: Vec<KeyEntry>,
@@ -69,19 +97,17 @@ pub fn process(_dab_request: LongKeyPressRequest) -> Result<String, DabError> {
         client: Option<String>,
     }
 
-    let start = Instant::now();
-
-    let client = if needs_direct_injection(_dab_request.keyCode.as_str()) {
-        get_focused_client()
-    } else {
-        None
+    let client = match client {
+        Some(client) => Some(client.to_string()),
+        None if needs_direct_injection(key_code) => get_focused_client(),
+        None => None,
     };
 
     let duration_s = (total_time as f64) / 1000.0;
 
     let key_entry = KeyEntry {
         keyCode: KeyCode,
-        modifiers: vec![],
+        modifiers: modifiers.to_vec(),
         delay: 0.0,
         duration: duration_s,
         client,
@@ -91,24 +117,55 @@ pub fn process(_dab_request: LongKeyPressRequest) -> Result<String, DabError> {
         keys: vec![key_entry],
     };
 
-    let request = GenerateKeyRequest {
-        jsonrpc: "2.0".into(),
-        id: 3,
-        method: "org.rdk.RDKShell.1.generateKey".into(),
-        params: req_params,
-    };
-
-    let json_string = serde_json::to_string(&request).unwrap();
-
     let deadline = start + Duration::from_millis(total_time);
 
-    http_post(json_string)?;
+    let _rdkresponse: RdkResponseSimple =
+        rdk_request_with_params("org.rdk.RDKShell.generateKey", req_params)?;
 
     let now = Instant::now();
     if now < deadline {
         thread::sleep(deadline.duration_since(now));
     }
 
-    // *******************************************************************
-    Ok(serde_json::to_string(&ResponseOperator).unwrap())
+    Ok(())
+}
+
+// Sends repeats of the pressed key until the deadline, then releases it.
+fn remote_control_hold(settings: &KeyHoldSettings, key_code: u16, modifiers: &[String], deadline: Instant) -> Result<(), DabError> {
+    let mut result = Ok(());
+    let mut next_repeat = Instant::now() + Duration::from_millis(settings.initial_delay_ms);
+    while settings.repeat_interval_ms > 0 && next_repeat < deadline {
+        thread::sleep(next_repeat.saturating_duration_since(Instant::now()));
+        result = remote_control_key("press", settings, key_code, modifiers);
+        if result.is_err() {
+            break;
+        }
+        next_repeat += Duration::from_millis(settings.repeat_interval_ms);
+    }
+
+    if result.is_ok() {
+        thread::sleep(deadline.saturating_duration_since(Instant::now()));
+    }
+
+    // The key is released even after a failed repeat, so that it doesn't stay down.
+    let released = remote_control_key("release", settings, key_code, modifiers);
+    result.and(released)
+}
+
+fn remote_control_key(action: &str, settings: &KeyHoldSettings, key_code: u16, modifiers: &[String]) -> Result<(), DabError> {
+    // RemoteControl names the modifier keys of each side.
+    let modifiers: Vec<String> = modifiers
+        .iter()
+        .map(|modifier| format!("left{}", modifier))
+        .collect();
+
+    let _rdkresponse: Value = rdk_request_with_params(
+        &format!("RemoteControl.1.{}", action),
+        json!({
+            "device": settings.device,
+            "code": key_code,
+            "modifiers": modifiers,
+        }),
+    )?;
+    Ok(())
 }
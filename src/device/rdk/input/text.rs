@@ -0,0 +1,143 @@
+use crate::dab::structs::DabError;
+use crate::dab::structs::InputTextRequest;
+use crate::dab::structs::InputTextResponse;
+use crate::device::rdk::applications::catalog::{get_app, get_app_by_callsign, DeeplinkStrategy, TextInputStrategy};
+use crate::device::rdk::applications::get_state::{get_app_state, AppState};
+use crate::device::rdk::applications::launch::{get_visibility, move_to_front, send_deeplink, set_focus, set_visibility};
+use crate::device::rdk::input::key::get_focused_client;
+use crate::device::rdk::input::key_press::inject_key;
+use std::thread;
+use std::time::Duration;
+use urlencoding::encode;
+
+// Pause between characters, so that on-screen keyboards don't drop keys.
+const CHARACTER_DELAY_MS: u64 = 50;
+const SHIFT: &str = "shift";
+
+#[allow(non_snake_case)]
+#[allow(dead_code)]
+#[allow(unused_mut)]
+pub fn process(_dab_request: InputTextRequest) -> Result<String, DabError> {
+    let mut ResponseOperator = InputTextResponse::default();
+    // *** Fill in the fields of the struct InputTextResponse here ***
+
+    if _dab_request.text.is_empty() {
+        return Err(DabError::Err400(
+            "request missing 'text' parameter".to_string(),
+        ));
+    }
+
+    let target_app = _dab_request.appId.as_deref().filter(|app_id| !app_id.is_empty());
+    let app = match target_app {
+        Some(app_id) => {
+            // Text is only typed into a running app that isn't suspended or hibernated; the app is
+            // not launched or resumed for it.
+            let app = get_app(app_id);
+            match get_app_state(&app.callsign())? {
+                AppState::Visible | AppState::Invisible => Some(app),
+                state => {
+                    return Err(DabError::Err400(
+                        format!("{} is not running or is suspended ({:?}).", app_id, state),
+                    ));
+                }
+            }
+        }
+        None => get_focused_client().map(|client| get_app_by_callsign(&client)),
+    };
+
+    // Apps whose keyboards don't take raw key events get the text as a deeplink.
+    if let Some(app) = app.as_ref() {
+        if let TextInputStrategy::Deeplink { parameter } = &app.text_input {
+            let param = parameter.replace("{text}", &encode(&_dab_request.text));
+            if target_app.is_some() {
+                bring_to_front(&app.callsign())?;
+            }
+            if let DeeplinkStrategy::Relaunch = send_deeplink(app, &[param])? {
+                return Err(DabError::Err500(
+                    format!("{} only takes deeplinks by relaunching; text can't be passed to it.", app.app_id),
+                ));
+            }
+            ResponseOperator.method = "deeplink".to_string();
+            return Ok(serde_json::to_string(&ResponseOperator).unwrap());
+        }
+    }
+
+    // Check the whole text before typing any of it.
+    let keys = _dab_request
+        .text
+        .chars()
+        .map(|c| {
+            character_key(c).ok_or(DabError::Err400(
+                format!("character '{}' is not on the keyboard layout", c.escape_default()),
+            ))
+        })
+        .collect::<Result<Vec<(u16, bool)>, DabError>>()?;
+
+    if let (Some(app), Some(_)) = (app.as_ref(), target_app) {
+        bring_to_front(&app.callsign())?;
+    }
+
+    for (key_code, shift) in keys {
+        let modifiers = if shift { vec![SHIFT.to_string()] } else { vec![] };
+        inject_key(key_code, &modifiers)?;
+        thread::sleep(Duration::from_millis(CHARACTER_DELAY_MS));
+    }
+    ResponseOperator.method = "keys".to_string();
+
+    // *******************************************************************
+    Ok(serde_json::to_string(&ResponseOperator).unwrap())
+}
+
+// Shows the app on top of the others and gives it the keys.
+fn bring_to_front(callsign: &str) -> Result<(), DabError> {
+    if !get_visibility(callsign.to_string())? {
+        set_visibility(callsign.to_string(), true)?;
+    }
+    move_to_front(callsign)?;
+    set_focus(callsign)
+}
+
+// Returns the key code of the character on a US keyboard layout, and whether shift is needed.
+fn character_key(c: char) -> Option<(u16, bool)> {
+    let key = match c {
+        'a'..='z' => (c as u16 - 'a' as u16 + 65, false),
+        'A'..='Z' => (c as u16 - 'A' as u16 + 65, true),
+        '0'..='9' => (c as u16, false),
+        ' ' => (32, false),
+        '\n' => (13, false),
+        '-' => (189, false),
+        '=' => (187, false),
+        '[' => (219, false),
+        ']' => (221, false),
+        '\\' => (220, false),
+        ';' => (186, false),
+        '\'' => (222, false),
+        ',' => (188, false),
+        '.' => (190, false),
+        '/' => (191, false),
+        '`' => (192, false),
+        '!' => (49, true),
+        '@' => (50, true),
+        '#' => (51, true),
+        '$' => (52, true),
+        '%' => (53, true),
+        '^' => (54, true),
+        '&' => (55, true),
+        '*' => (56, true),
+        '(' => (57, true),
+        ')' => (48, true),
+        '_' => (189, true),
+        '+' => (187, true),
+        '{' => (219, true),
+        '}' => (221, true),
+        '|' => (220, true),
+        ':' => (186, true),
+        '"' => (222, true),
+        '<' => (188, true),
+        '>' => (190, true),
+        '?' => (191, true),
+        '~' => (192, true),
+        _ => return None,
+    };
+    Some(key)
+}
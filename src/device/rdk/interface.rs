@@ -3,6 +3,8 @@
 use crate::dab::structs::AudioOutputMode;
 use crate::dab::structs::AudioVolume;
 use crate::dab::structs::DabError;
+use crate::device::rdk::applications::catalog::{get_app, get_catalog, BackgroundExitPolicy};
+use crate::device::rdk::applications::state_cache;
 use futures::executor::block_on;
 use futures_util::stream::StreamExt;
 use futures_util::SinkExt;
@@ -11,10 +13,12 @@ use serde::{de::DeserializeOwned, de::Visitor, Deserialize, Deserializer, Serial
 use serde_json::Value;
 use serde_json::json;
 use std::fmt;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::Read;
 use std::io::Write;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Mutex;
 use std::sync::OnceLock;
 use std::{thread, time};
 use surf::Client;
@@ -94,7 +98,14 @@ pub fn init(device_ip: &str, debug: bool) {
     let _ = DEVICE_ADDRESS.set(device_ip.to_string());
     let _ = DEBUG.set(debug);
 
+    state_cache::start();
+
     if *DEBUG.get().unwrap_or(&false) {
+        for app in get_catalog() {
+            for (key, value) in app.lifecycle_timeouts.iter() {
+                println!("{:<15} - {:<30} = {:>5}ms.", app.app_id.to_lowercase(), key, value);
+            }
+        }
         for app in APP_LIFECYCLE_TIMEOUTS.keys() {
             for (key, value) in APP_LIFECYCLE_TIMEOUTS.get(app).unwrap() {
                 println!("{:<15} - {:<30} = {:>5}ms.", app, key, value);
@@ -108,25 +119,164 @@ pub fn is_local_device() -> bool {
     matches!(addr, "localhost" | "127.0.0.1" | "::1")
 }
 
-lazy_static! {
-    static ref RDK_DEVICE_ID: String = request_device_id().unwrap();
+// ############################### Device ID ###############################
+
+static RDK_DEVICE_ID: OnceLock<String> = OnceLock::new();
+
+const DEVICE_ID_PERSIST_PATH: &str = "/opt/dab_device_id";
+const DEVICE_ID_RETRY_INITIAL_MS: u64 = 500;
+const DEVICE_ID_RETRY_MAX_MS: u64 = 30000;
+// How long Thunder is given to answer before a persisted ID is used instead.
+const DEVICE_ID_FALLBACK_AFTER_MS: u64 = 60000;
+
+// The persisted Device ID, with the source it came from.
+#[derive(Serialize, Deserialize)]
+struct PersistedDeviceId {
+    source: DeviceIdSource,
+    id: String,
 }
 
+// Returns the Device ID, resolving it on first use. See resolve_device_id().
 pub fn get_device_id() -> Result<String, DabError> {
-    Ok(RDK_DEVICE_ID.to_string())
+    if let Some(device_id) = RDK_DEVICE_ID.get() {
+        return Ok(device_id.clone());
+    }
+    let device_id = resolve_device_id()?;
+    Ok(RDK_DEVICE_ID.get_or_init(|| device_id).clone())
 }
 
-fn request_device_id() -> Result<String, DabError> {
-    let json_string =
-        "{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"org.rdk.System.getDeviceInfo\",\"params\":{\"params\":[\"estb_mac\"]}}".to_string();
-    let response = http_post(json_string)?;
-    let rdkresponse: serde_json::Value = serde_json::from_str(&response).unwrap();
-    let device_id = rdkresponse["result"]["estb_mac"]
-        .as_str()
-        .ok_or(DabError::Err500(
-            "RDK Error: org.rdk.System.getDeviceInfo.result.estb_mac not found".to_string(),
-        ))?;
-    Ok(device_id.replace(":", "").to_string())
+// Resolve the Device ID from the source configured in /etc/dab/settings.json.
+// Thunder may not be up yet during boot, so Thunder lookups are retried with exponential backoff
+// until it answers. Every resolved ID is persisted with its source; when Thunder hasn't answered
+// for a while, an ID persisted from the same source is used instead so the Device ID stays stable
+// across restarts.
+// Returns DabError only on configuration errors, which are never retried.
+fn resolve_device_id() -> Result<String, DabError> {
+    let settings = SETTINGS.device_id.clone().unwrap_or_default();
+    validate_device_id_settings(&settings)?;
+
+    let persist_path = settings
+        .persist_path
+        .clone()
+        .unwrap_or(DEVICE_ID_PERSIST_PATH.to_string());
+    let persisted_id = read_persisted_device_id(&persist_path, &settings.source);
+
+    match settings.source {
+        DeviceIdSource::Fixed => {
+            return Ok(settings.value.unwrap_or_default().trim().to_string());
+        }
+        DeviceIdSource::File => {
+            let path = settings.path.clone().unwrap_or_default();
+            let device_id = read_platform_config_json(&path)
+                .map_err(|e| {
+                    DabError::Err500(format!("Unable to read the Device ID from {}: {:?}", path, e))
+                })?
+                .trim()
+                .to_string();
+            if device_id.is_empty() {
+                return Err(DabError::Err500(format!("{} holds an empty Device ID", path)));
+            }
+            persist_device_id(&persist_path, persisted_id.as_deref(), &settings.source, &device_id);
+            return Ok(device_id);
+        }
+        _ => {}
+    }
+
+    let started = time::Instant::now();
+    let mut retry_delay = DEVICE_ID_RETRY_INITIAL_MS;
+    loop {
+        match request_device_id(&settings) {
+            Ok(device_id) => {
+                persist_device_id(&persist_path, persisted_id.as_deref(), &settings.source, &device_id);
+                return Ok(device_id);
+            }
+            Err(err) => {
+                if let Some(device_id) = persisted_id.as_ref() {
+                    if started.elapsed() >= time::Duration::from_millis(DEVICE_ID_FALLBACK_AFTER_MS) {
+                        println!("RDK: Device ID lookup failed ({:?}); using persisted ID from {}.", err, persist_path);
+                        return Ok(device_id.clone());
+                    }
+                }
+                println!("RDK: Device ID not available yet ({:?}); retrying in {}ms.", err, retry_delay);
+                thread::sleep(time::Duration::from_millis(retry_delay));
+                retry_delay = (retry_delay * 2).min(DEVICE_ID_RETRY_MAX_MS);
+            }
+        }
+    }
+}
+
+fn validate_device_id_settings(settings: &DeviceIdSettings) -> Result<(), DabError> {
+    let is_set = |field: &Option<String>| field.as_deref().is_some_and(|v| !v.trim().is_empty());
+    match settings.source {
+        DeviceIdSource::Unknown => Err(DabError::Err500(
+            "device_id.source must be one of 'estb_mac', 'serial_number', 'fixed' or 'file'".to_string(),
+        )),
+        DeviceIdSource::Fixed if !is_set(&settings.value) => Err(DabError::Err500(
+            "device_id.source is 'fixed' but device_id.value is not set".to_string(),
+        )),
+        DeviceIdSource::File if !is_set(&settings.path) => Err(DabError::Err500(
+            "device_id.source is 'file' but device_id.path is not set".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+// Returns the persisted Device ID when it was resolved from `source`. IDs from another source,
+// or persisted without one, are ignored.
+fn read_persisted_device_id(persist_path: &str, source: &DeviceIdSource) -> Option<String> {
+    let persisted = read_platform_config_json(persist_path).ok()?;
+    let persisted = serde_json::from_str::<PersistedDeviceId>(&persisted).ok()?;
+    let id = persisted.id.trim().to_string();
+    (persisted.source == *source && !id.is_empty()).then_some(id)
+}
+
+fn persist_device_id(persist_path: &str, persisted_id: Option<&str>, source: &DeviceIdSource, device_id: &str) {
+    if persisted_id != Some(device_id) {
+        let persisted = PersistedDeviceId {
+            source: source.clone(),
+            id: device_id.to_string(),
+        };
+        if let Err(e) = std::fs::write(persist_path, serde_json::to_string(&persisted).unwrap()) {
+            println!("Unable to persist Device ID to {}: {}", persist_path, e);
+        }
+    }
+}
+
+// Looks the Device ID up in Thunder. Errors mean Thunder isn't ready and are worth retrying.
+fn request_device_id(settings: &DeviceIdSettings) -> Result<String, DabError> {
+    let device_id = match settings.source {
+        DeviceIdSource::EstbMac => {
+            let json_string =
+                "{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"org.rdk.System.getDeviceInfo\",\"params\":{\"params\":[\"estb_mac\"]}}".to_string();
+            let response = http_post(json_string)?;
+            let rdkresponse: serde_json::Value = serde_json::from_str(&response)
+                .map_err(|e| DabError::Err500(format!("Failed to parse response: {}", e)))?;
+            rdkresponse["result"]["estb_mac"]
+                .as_str()
+                .ok_or(DabError::Err500(
+                    "RDK Error: org.rdk.System.getDeviceInfo.result.estb_mac not found".to_string(),
+                ))?
+                .replace(":", "")
+        }
+        DeviceIdSource::SerialNumber => {
+            get_thunder_property("DeviceInfo.serialnumber", "serialnumber")?
+        }
+        _ => {
+            return Err(DabError::Err500(format!(
+                "Device ID source {:?} is not looked up in Thunder",
+                settings.source
+            )))
+        }
+    };
+
+    let device_id = device_id.trim().to_string();
+    if device_id.is_empty() {
+        return Err(DabError::Err500(format!(
+            "Empty Device ID from source {:?}",
+            settings.source
+        )));
+    }
+    Ok(device_id)
 }
 
 pub fn http_download(url: String) -> Result<(), DabError> {
@@ -145,12 +295,30 @@ pub fn http_download(url: String) -> Result<(), DabError> {
     }
 }
 
+// Host and port of the Thunder JSON-RPC endpoint.
+fn thunder_address() -> String {
+    #[cfg(test)]
+    if let Some(address) = TEST_THUNDER_ADDRESS.lock().unwrap().clone() {
+        return address;
+    }
+    format!("{}:9998", DEVICE_ADDRESS.get().expect("init() not called"))
+}
+
+// Sends the Thunder requests of the calling tests to a mock Thunder, and forgets what was learnt
+// from the previous one.
+#[cfg(test)]
+static TEST_THUNDER_ADDRESS: Mutex<Option<String>> = Mutex::new(None);
+
+#[cfg(test)]
+pub fn use_test_thunder(address: Option<String>) {
+    *TEST_THUNDER_ADDRESS.lock().unwrap() = address;
+    *PLUGIN_VERSIONS.lock().unwrap() = None;
+    NEGOTIATED_METHODS.lock().unwrap().clear();
+}
+
 pub fn http_post(json_string: String) -> Result<String, DabError> {
     let client = Client::new();
-    let rdk_address = format!(
-        "http://{}:9998/jsonrpc",
-        DEVICE_ADDRESS.get().expect("init() not called")
-    );
+    let rdk_address = format!("http://{}/jsonrpc", thunder_address());
 
     if *DEBUG.get().unwrap_or(&false) {
         println!("RDK request: {}", json_string);
@@ -196,10 +364,7 @@ pub fn http_post(json_string: String) -> Result<String, DabError> {
 }
 
 pub async fn ws_open() -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, DabError> {
-    let rdk_address = format!(
-        "ws://{}:9998/jsonrpc",
-        DEVICE_ADDRESS.get().expect("init() not called")
-    );
+    let rdk_address = format!("ws://{}/jsonrpc", thunder_address());
     let url = Url::parse(&rdk_address).expect("Invalid WebSocket URL");
 
     connect_async(url)
@@ -249,6 +414,27 @@ pub async fn ws_receive(
     }
 }
 
+// Waits for the next message of an event subscription; unlike ws_receive, there is no timeout.
+pub async fn ws_receive_event(
+    ws_stream: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+) -> Result<Value, DabError> {
+    loop {
+        match ws_stream.next().await {
+            Some(Ok(Message::Text(text))) => {
+                return serde_json::from_str(&text)
+                    .map_err(|e| DabError::Err500(format!("Invalid JSON: {}", e)));
+            }
+            Some(Ok(_)) => continue,
+            Some(Err(e)) => return Err(DabError::Err500(format!("Error reading message: {:?}", e))),
+            None => {
+                return Err(DabError::Err500(
+                    "The WebSocket stream has been closed by the server".to_string(),
+                ))
+            }
+        }
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Deserialize)]
 pub struct RdkResponse<T> {
@@ -283,6 +469,44 @@ fn rdk_request_impl<P: Serialize, R: DeserializeOwned>(
     method: &str,
     params: Option<P>,
 ) -> Result<R, DabError> {
+    let params = match params {
+        Some(params) => Some(serde_json::to_value(params).map_err(|e| DabError::Err500(e.to_string()))?),
+        None => None,
+    };
+
+    let mut result = Err(DabError::Err500(format!("No API version available for {}", method)));
+    for candidate in thunder_methods(method) {
+        // Transport errors say nothing about the method, so nothing is remembered.
+        let response = rdk_post_request(&candidate, params.as_ref())?;
+        result = rdk_parse_response(&candidate, response);
+        if let Err(err) = &result {
+            if is_unknown_method_error(err) {
+                if *DEBUG.get().unwrap_or(&false) {
+                    println!("RDK: {} is not available; trying the next API version.", candidate);
+                }
+                continue;
+            }
+        }
+        // Thunder answered, even if with an error, so the method exists in this version.
+        remember_thunder_method(method, &candidate);
+        break;
+    }
+    result
+}
+
+fn rdk_send_request<P: Serialize, R: DeserializeOwned>(
+    method: &str,
+    params: Option<P>,
+) -> Result<R, DabError> {
+    let response = rdk_post_request(method, params)?;
+    rdk_parse_response(method, response)
+}
+
+// Sends a JSON-RPC request to Thunder. Errors are transport errors only.
+fn rdk_post_request<P: Serialize>(
+    method: &str,
+    params: Option<P>,
+) -> Result<serde_json::Value, DabError> {
     #[derive(Serialize)]
     struct RdkRequest<P> {
         jsonrpc: String,
@@ -292,14 +516,10 @@ fn rdk_request_impl<P: Serialize, R: DeserializeOwned>(
         params: Option<P>,
     }
 
-    static mut JSONRPC_ID: i32 = 1;
-
-    let id;
+    // Requests are sent from several threads.
+    static JSONRPC_ID: AtomicI32 = AtomicI32::new(1);
 
-    unsafe {
-        id = JSONRPC_ID;
-        JSONRPC_ID += 1;
-    }
+    let id = JSONRPC_ID.fetch_add(1, Ordering::Relaxed);
 
     let request = RdkRequest {
         jsonrpc: "2.0".into(),
@@ -310,11 +530,14 @@ fn rdk_request_impl<P: Serialize, R: DeserializeOwned>(
     let json_string = serde_json::to_string(&request).unwrap();
     let response = http_post(json_string)?;
 
-    let val: serde_json::Value = match serde_json::from_str(&response) {
-        Ok(val) => val,
-        Err(e) => return Err(DabError::Err500(e.to_string())),
-    };
+    serde_json::from_str(&response).map_err(|e| DabError::Err500(e.to_string()))
+}
 
+// Turns a Thunder response into its result, or into the error Thunder reported.
+fn rdk_parse_response<R: DeserializeOwned>(
+    method: &str,
+    val: serde_json::Value,
+) -> Result<R, DabError> {
     if val["error"] != serde_json::Value::Null {
         return Err(DabError::Err500(
             val["error"]["message"].as_str().unwrap().into(),
@@ -333,24 +556,175 @@ fn rdk_request_impl<P: Serialize, R: DeserializeOwned>(
     Ok(res)
 }
 
+// ############################### Thunder API Version Negotiation ###############################
+
+// Thunder API versions the adapter can speak, newest first. Calls to these plugins are sent to the
+// newest version the plugin reports in Controller.1.status, falling back to older versions when the
+// plugin doesn't know the method. Plugins not listed here are called exactly as requested.
+const THUNDER_API_VERSIONS: &[(&str, &[u32])] = &[
+    ("Controller", &[1]),
+    ("org.rdk.RDKShell", &[1]),
+    ("org.rdk.DisplaySettings", &[2, 1]),
+    ("org.rdk.System", &[2, 1]),
+    ("org.rdk.UserSettings", &[1]),
+];
+
+lazy_static! {
+    // Major version of every configured plugin, as reported by Controller.1.status.
+    static ref PLUGIN_VERSIONS: Mutex<Option<HashMap<String, Option<u32>>>> = Mutex::new(None);
+    // Unversioned method name => method name that answered last time.
+    static ref NEGOTIATED_METHODS: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+}
+
+fn with_plugin_versions<T>(f: impl FnOnce(&HashMap<String, Option<u32>>) -> T) -> Option<T> {
+    if let Some(versions) = PLUGIN_VERSIONS.lock().unwrap().as_ref() {
+        return Some(f(versions));
+    }
+
+    // Fetched without holding the lock, so that callers don't queue behind a Thunder that doesn't
+    // answer. Not cached on failure; Thunder may still be starting up.
+    let response = rdk_send_request::<(), Value>("Controller.1.status", None).ok()?;
+    let versions = response["result"]
+        .as_array()?
+        .iter()
+        .filter_map(|plugin| {
+            let callsign = plugin["callsign"].as_str()?.to_string();
+            let major = plugin["version"]["major"].as_u64().map(|v| v as u32);
+            Some((callsign, major))
+        })
+        .collect();
+    let mut plugin_versions = PLUGIN_VERSIONS.lock().unwrap();
+    Some(f(plugin_versions.get_or_insert(versions)))
+}
+
+// Returns the major API version reported by the plugin, if the Thunder release reports one.
+pub fn get_plugin_major_version(callsign: &str) -> Option<u32> {
+    with_plugin_versions(|versions| versions.get(callsign).cloned().flatten()).flatten()
+}
+
+// Returns false only when Thunder answered and the plugin isn't configured on the device.
+pub fn thunder_plugin_exists(callsign: &str) -> bool {
+    with_plugin_versions(|versions| versions.contains_key(callsign)).unwrap_or(true)
+}
+
+// Split "<callsign>[.<version>].<method>[@<designator>]" into callsign and method.
+fn split_thunder_method(method: &str) -> Option<(&str, String)> {
+    let (name, designator) = match method.split_once('@') {
+        Some((name, designator)) => (name, format!("@{}", designator)),
+        None => (method, String::new()),
+    };
+    let (prefix, name) = name.rsplit_once('.')?;
+    let callsign = match prefix.rsplit_once('.') {
+        Some((callsign, version)) if version.parse::<u32>().is_ok() => callsign,
+        _ => prefix,
+    };
+    Some((callsign, format!("{}{}", name, designator)))
+}
+
+// Returns the method names to try for a request, best API version first.
+fn thunder_methods(method: &str) -> Vec<String> {
+    let Some((callsign, name)) = split_thunder_method(method) else {
+        return vec![method.to_string()];
+    };
+    let Some((_, versions)) = THUNDER_API_VERSIONS.iter().find(|(c, _)| *c == callsign) else {
+        return vec![method.to_string()];
+    };
+
+    if let Some(negotiated) = NEGOTIATED_METHODS.lock().unwrap().get(&format!("{}.{}", callsign, name)) {
+        return vec![negotiated.clone()];
+    }
+
+    // Without a reported version every known version is tried, newest first.
+    let mut candidates: Vec<u32> = match get_plugin_major_version(callsign) {
+        Some(major) => versions.iter().copied().filter(|v| *v <= major).collect(),
+        None => versions.to_vec(),
+    };
+    if candidates.is_empty() {
+        candidates.extend(versions.iter().min());
+    }
+
+    candidates
+        .iter()
+        .map(|version| format!("{}.{}.{}", callsign, version, name))
+        .collect()
+}
+
+fn remember_thunder_method(method: &str, negotiated: &str) {
+    if let Some((callsign, name)) = split_thunder_method(method) {
+        if THUNDER_API_VERSIONS.iter().any(|(c, _)| *c == callsign) {
+            NEGOTIATED_METHODS
+                .lock()
+                .unwrap()
+                .insert(format!("{}.{}", callsign, name), negotiated.to_string());
+        }
+    }
+}
+
+fn is_unknown_method_error(err: &DabError) -> bool {
+    let message = match err {
+        DabError::Err400(msg) | DabError::Err500(msg) | DabError::Err501(msg) => msg,
+    };
+    ["Unknown method", "ERROR_UNKNOWN_METHOD", "Requested version is not supported"]
+        .iter()
+        .any(|marker| message.contains(marker))
+}
+
+// Returns the best method name for a call that can't go through rdk_request(), e.g. when the
+// response needs custom handling.
+pub fn thunder_method(method: &str) -> String {
+    thunder_methods(method)
+        .into_iter()
+        .next()
+        .unwrap_or(method.to_string())
+}
+
+// ############################### Capability Probing ###############################
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AppLifecycleBackend {
+    // RDK-V: applications are run by RDKShell.
+    RDKShell,
+    // RDK-E: applications are run by the AppManager and LifecycleManager plugins.
+    AppManager,
+}
+
+static APP_LIFECYCLE_BACKEND: OnceLock<AppLifecycleBackend> = OnceLock::new();
+
+// Returns the plugin set that runs applications on this device. Probed once Thunder answers;
+// RDKShell is assumed until then.
+pub fn get_app_lifecycle_backend() -> AppLifecycleBackend {
+    if let Some(backend) = APP_LIFECYCLE_BACKEND.get() {
+        return *backend;
+    }
+
+    let probed = with_plugin_versions(|versions| {
+        if versions.contains_key("org.rdk.AppManager")
+            && versions.contains_key("org.rdk.LifecycleManager")
+        {
+            AppLifecycleBackend::AppManager
+        } else {
+            AppLifecycleBackend::RDKShell
+        }
+    });
+
+    match probed {
+        Some(backend) => *APP_LIFECYCLE_BACKEND.get_or_init(|| {
+            println!("Using {:?} application lifecycle backend.", backend);
+            backend
+        }),
+        None => AppLifecycleBackend::RDKShell,
+    }
+}
+
 // Function to activate a service.
 // Parameters: service: The service to activate.
 // Returns Ok on success else DabError.
 pub fn service_activate(service: String) -> Result<(), DabError> {
-    //#########Controller.1.activate#########
-    let activate_payload = json!({
-        "jsonrpc":"2.0",
-        "id":1,
-        "method":"Controller.1.activate",
-        "params":{
-            "callsign":service.clone()
-        }
-    }).to_string();
-    let response = http_post(activate_payload)?;
-    let response_value: serde_json::Value = serde_json::from_str(&response)
-        .map_err(|e| DabError::Err500(format!("Failed to parse response: {}", e)))?;
+    //#########Controller.activate#########
+    let response_value: Value =
+        rdk_request_with_params("Controller.activate", json!({ "callsign": service.clone() }))?;
     if response_value.get("result").is_none() {
-        return Err(DabError::Err500(format!("Key 'result' not found in response for method 'Controller.1.activate'.")));
+        return Err(DabError::Err500("Key 'result' not found in response for method 'Controller.activate'.".to_string()));
     }
     thread::sleep(time::Duration::from_millis(200));
     if get_service_state(service.as_str())?.to_lowercase() != "activated" {
@@ -363,20 +737,11 @@ pub fn service_activate(service: String) -> Result<(), DabError> {
 // Parameters: service: The service to deactivate.
 // Returns Ok on success else DabError.
 pub fn service_deactivate(service: String) -> Result<(), DabError> {
-    //#########Controller.1.deactivate#########
-    let activate_payload = json!({
-        "jsonrpc":"2.0",
-        "id":1,
-        "method":"Controller.1.deactivate",
-        "params":{
-            "callsign":service.clone()
-        }
-    }).to_string();
-    let response = http_post(activate_payload)?;
-    let response_value: serde_json::Value = serde_json::from_str(&response)
-        .map_err(|e| DabError::Err500(format!("Failed to parse response: {}", e)))?;
+    //#########Controller.deactivate#########
+    let response_value: Value =
+        rdk_request_with_params("Controller.deactivate", json!({ "callsign": service.clone() }))?;
     if response_value.get("result").is_none() {
-        return Err(DabError::Err500(format!("Key 'result' not found in response for method 'Controller.1.activate'.")));
+        return Err(DabError::Err500("Key 'result' not found in response for method 'Controller.deactivate'.".to_string()));
     }
     thread::sleep(time::Duration::from_millis(200));
     if get_service_state(service.as_str())?.to_lowercase() != "deactivated" {
@@ -455,12 +820,137 @@ pub fn service_is_available(service: &str) -> Result<bool, DabError> {
     }
 */
 
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum DeviceIdSource {
+    #[default]
+    EstbMac,
+    SerialNumber,
+    Fixed,
+    File,
+    // Any other value; rejected when the Device ID is resolved.
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+struct DeviceIdSettings {
+    #[serde(default)]
+    source: DeviceIdSource,
+    // Device ID used as-is when source is "fixed".
+    value: Option<String>,
+    // File holding the Device ID when source is "file".
+    path: Option<String>,
+    // Where the resolved Device ID is kept; defaults to /opt/dab_device_id.
+    persist_path: Option<String>,
+}
+
+// Makes room before a launch by suspending, hibernating or stopping least recently used
+// background apps.
+#[derive(Deserialize, Debug, Clone)]
+pub struct LaunchPolicySettings {
+    // Free memory in KB wanted before launching an app.
+    pub min_free_memory_kb: Option<u32>,
+    // Other apps that may stay untouched by the action.
+    pub max_background_apps: Option<usize>,
+    #[serde(default)]
+    pub action: BackgroundExitPolicy,
+    // Clients the policy never touches, such as the resident UI.
+    #[serde(default = "default_protected_clients")]
+    pub protected_clients: Vec<String>,
+}
+
+fn default_protected_clients() -> Vec<String> {
+    vec!["ResidentApp".to_string()]
+}
+
+// How input/long-key-press holds keys.
+#[derive(Deserialize, Debug, Clone)]
+pub struct KeyHoldSettings {
+    // Hold keys with RemoteControl press/release when the plugin is present; otherwise
+    // RDKShell.generateKey is used.
+    #[serde(default = "default_key_hold_remote_control")]
+    pub remote_control: bool,
+    // RemoteControl device whose key map takes the DAB key codes.
+    #[serde(default = "default_key_hold_device")]
+    pub device: String,
+    // Time from key down to the first repeat, and between repeats; 0 disables repeats.
+    #[serde(default = "default_key_hold_initial_delay_ms")]
+    pub initial_delay_ms: u64,
+    #[serde(default = "default_key_hold_repeat_interval_ms")]
+    pub repeat_interval_ms: u64,
+}
+
+fn default_key_hold_remote_control() -> bool {
+    true
+}
+
+fn default_key_hold_device() -> String {
+    "Web".to_string()
+}
+
+fn default_key_hold_initial_delay_ms() -> u64 {
+    500
+}
+
+fn default_key_hold_repeat_interval_ms() -> u64 {
+    100
+}
+
+impl Default for KeyHoldSettings {
+    fn default() -> Self {
+        KeyHoldSettings {
+            remote_control: default_key_hold_remote_control(),
+            device: default_key_hold_device(),
+            initial_delay_ms: default_key_hold_initial_delay_ms(),
+            repeat_interval_ms: default_key_hold_repeat_interval_ms(),
+        }
+    }
+}
+
+// Where output/image receives the upload from org.rdk.ScreenCapture.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ScreenshotUploadSettings {
+    #[serde(default = "default_screenshot_upload_bind_address")]
+    pub bind_address: String,
+    // 0 binds an ephemeral port.
+    #[serde(default = "default_screenshot_upload_port")]
+    pub port: u16,
+    // Host put in the upload URL; by default the bind address, or the local IP when bound to all
+    // interfaces.
+    pub advertised_host: Option<String>,
+}
+
+fn default_screenshot_upload_bind_address() -> String {
+    "0.0.0.0".to_string()
+}
+
+fn default_screenshot_upload_port() -> u16 {
+    7878
+}
+
+impl Default for ScreenshotUploadSettings {
+    fn default() -> Self {
+        ScreenshotUploadSettings {
+            bind_address: default_screenshot_upload_bind_address(),
+            port: default_screenshot_upload_port(),
+            advertised_host: None,
+        }
+    }
+}
+
 // TODO: Extend this struct, so it contains more settings value,
 // instead of having them in different files and in /opt
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Default)]
 struct Settings {
     supported_languages: Option<Vec<String>>,
-    audio_volume_range: Option<AudioVolume>
+    audio_volume_range: Option<AudioVolume>,
+    device_id: Option<DeviceIdSettings>,
+    launch_policy: Option<LaunchPolicySettings>,
+    // Publishes launch and exit timings to dab/<device-id>/applications/metrics.
+    lifecycle_metrics: Option<bool>,
+    key_hold: Option<KeyHoldSettings>,
+    screenshot_upload: Option<ScreenshotUploadSettings>,
 }
 
 lazy_static! {
@@ -480,26 +970,135 @@ lazy_static! {
         }
 
         println!("Using default settings.");
-        Settings {
-            supported_languages: None,
-            audio_volume_range: None
+        Settings::default()
+    };
+}
+
+// How a key is sent: with RDKShell.injectKey, or with RDKShell.generateKey to the focused client to
+// bypass platform-level key intercepts.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyInjection {
+    InjectKey,
+    GenerateKey,
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyMapping {
+    pub code: u16,
+    pub injection: KeyInjection,
+}
+
+impl KeyMapping {
+    fn new(key: &str, code: u16) -> KeyMapping {
+        KeyMapping {
+            code,
+            injection: default_key_injection(key),
+        }
+    }
+}
+
+// Volume and mute keys are intercepted by the platform unless sent to the focused app.
+fn default_key_injection(key: &str) -> KeyInjection {
+    match key {
+        "KEY_VOLUME_UP" | "KEY_VOLUME_DOWN" | "KEY_MUTE" => KeyInjection::GenerateKey,
+        _ => KeyInjection::InjectKey,
+    }
+}
+
+// Keymap entries are either the RDK key code or {"code": 403, "injection": "generate_key"}. A code
+// of 0 leaves the key unmapped.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+enum KeyMappingConfig {
+    Code(u16),
+    Mapping {
+        code: u16,
+        injection: Option<KeyInjection>,
+    },
+}
+
+fn merge_keymap(keycode_map: &mut HashMap<String, KeyMapping>, entries: HashMap<String, KeyMappingConfig>) {
+    for (key, entry) in entries {
+        let mapping = match entry {
+            KeyMappingConfig::Code(code) => KeyMapping::new(&key, code),
+            KeyMappingConfig::Mapping { code, injection } => KeyMapping {
+                code,
+                injection: injection.unwrap_or_else(|| default_key_injection(&key)),
+            },
+        };
+        keycode_map.insert(key, mapping);
+    }
+}
+
+// Keys of the devices a profile applies to, e.g. a model with its own remote. Profile files
+// must conform to the following format:
+/*
+    [
+        {
+            "name": "acme-x1",
+            "match": { "manufacturer": "Acme", "model": "X1*", "chipset": "BCM72180" },
+            "keys": {
+                "KEY_GUIDE": 458,
+                "KEY_RED": 403,
+                "KEY_MUTE": { "code": 173, "injection": "inject_key" },
+                "KEY_FAST_FORWARD": 0
+            }
         }
+    ]
+*/
+// "match" takes the DeviceInfo "manufacturer", "model" and "chipset"; values are compared ignoring
+// case and a trailing '*' matches any suffix. The first matching profile is applied over the keymap
+// once the device info could be read.
+#[derive(Deserialize, Debug)]
+struct KeymapProfile {
+    name: String,
+    #[serde(rename = "match", default)]
+    device: HashMap<String, String>,
+    #[serde(default)]
+    keys: HashMap<String, KeyMappingConfig>,
+}
 
+impl KeymapProfile {
+    // `device` holds the DeviceInfo properties the profiles match on.
+    fn matches_device(&self, device: &HashMap<String, String>) -> bool {
+        self.device.iter().all(|(property, pattern)| {
+            let Some(value) = device.get(property) else {
+                return false;
+            };
+            let (value, pattern) = (value.to_lowercase(), pattern.to_lowercase());
+            match pattern.strip_suffix('*') {
+                Some(prefix) => value.starts_with(prefix),
+                None => value == pattern,
+            }
+        })
+    }
+}
+
+fn read_keymap_profiles(path: &str) -> Vec<KeymapProfile> {
+    let Ok(json_file) = read_platform_config_json(path) else {
+        return vec![];
     };
+    match serde_json::from_str::<Vec<KeymapProfile>>(&json_file) {
+        Ok(profiles) => profiles,
+        Err(error) => {
+            eprintln!("Error while parsing {} {}", path, error);
+            vec![]
+        }
+    }
 }
 
 lazy_static! {
-    static ref RDK_KEYMAP: HashMap<String, u16> = {
+    // Keymap from the keymap files, before any profile is applied.
+    static ref BASE_KEYMAP: HashMap<String, KeyMapping> = {
         let mut keycode_map = HashMap::new();
         let mut keymap_file_found = false;
 
         if let Ok(json_file) = read_platform_config_json("/etc/dab/keymap.json") {
             keymap_file_found = true;
-            match serde_json::from_str::<HashMap<String, u16>>(&json_file) {
+            match serde_json::from_str::<HashMap<String, KeyMappingConfig>>(&json_file) {
                 Ok(new_keymap) => {
-                    for (key, value) in new_keymap {
-                        keycode_map.insert(key, value);
-                    }
+                    merge_keymap(&mut keycode_map, new_keymap);
                     println!("Loaded keymap from /etc/dab/keymap.json");
                 },
                 Err(error) => {
@@ -509,45 +1108,48 @@ lazy_static! {
         }
 
         if keymap_file_found == false {
-            keycode_map.insert(String::from("KEY_POWER"),116);
-            keycode_map.insert(String::from("KEY_HOME"),36);
-            keycode_map.insert(String::from("KEY_VOLUME_UP"),175);
-            keycode_map.insert(String::from("KEY_VOLUME_DOWN"),174);
-            keycode_map.insert(String::from("KEY_MUTE"),173);
-            keycode_map.insert(String::from("KEY_UP"),38);
-            keycode_map.insert(String::from("KEY_PAGE_UP"),33);
-            keycode_map.insert(String::from("KEY_PAGE_DOWN"),34);
-            keycode_map.insert(String::from("KEY_RIGHT"),39);
-            keycode_map.insert(String::from("KEY_DOWN"),40);
-            keycode_map.insert(String::from("KEY_LEFT"),37);
-            keycode_map.insert(String::from("KEY_ENTER"),13);
-            keycode_map.insert(String::from("KEY_BACK"),8);
-            keycode_map.insert(String::from("KEY_PLAY_PAUSE"),227);
-            keycode_map.insert(String::from("KEY_PAUSE"),19);
-            keycode_map.insert(String::from("KEY_REWIND"),224);
-            keycode_map.insert(String::from("KEY_FAST_FORWARD"),223);
-            keycode_map.insert(String::from("KEY_SKIP_REWIND"),34);
-            keycode_map.insert(String::from("KEY_SKIP_FAST_FORWARD"),33);
-            keycode_map.insert(String::from("KEY_0"),48);
-            keycode_map.insert(String::from("KEY_1"),49);
-            keycode_map.insert(String::from("KEY_2"),50);
-            keycode_map.insert(String::from("KEY_3"),51);
-            keycode_map.insert(String::from("KEY_4"),52);
-            keycode_map.insert(String::from("KEY_5"),53);
-            keycode_map.insert(String::from("KEY_6"),54);
-            keycode_map.insert(String::from("KEY_7"),55);
-            keycode_map.insert(String::from("KEY_8"),56);
-            keycode_map.insert(String::from("KEY_9"),57);
+            let mut default_codes: HashMap<String, u16> = HashMap::new();
+            default_codes.insert(String::from("KEY_POWER"),116);
+            default_codes.insert(String::from("KEY_HOME"),36);
+            default_codes.insert(String::from("KEY_VOLUME_UP"),175);
+            default_codes.insert(String::from("KEY_VOLUME_DOWN"),174);
+            default_codes.insert(String::from("KEY_MUTE"),173);
+            default_codes.insert(String::from("KEY_UP"),38);
+            default_codes.insert(String::from("KEY_PAGE_UP"),33);
+            default_codes.insert(String::from("KEY_PAGE_DOWN"),34);
+            default_codes.insert(String::from("KEY_RIGHT"),39);
+            default_codes.insert(String::from("KEY_DOWN"),40);
+            default_codes.insert(String::from("KEY_LEFT"),37);
+            default_codes.insert(String::from("KEY_ENTER"),13);
+            default_codes.insert(String::from("KEY_BACK"),8);
+            default_codes.insert(String::from("KEY_PLAY_PAUSE"),227);
+            default_codes.insert(String::from("KEY_PAUSE"),19);
+            default_codes.insert(String::from("KEY_REWIND"),224);
+            default_codes.insert(String::from("KEY_FAST_FORWARD"),223);
+            default_codes.insert(String::from("KEY_SKIP_REWIND"),34);
+            default_codes.insert(String::from("KEY_SKIP_FAST_FORWARD"),33);
+            default_codes.insert(String::from("KEY_0"),48);
+            default_codes.insert(String::from("KEY_1"),49);
+            default_codes.insert(String::from("KEY_2"),50);
+            default_codes.insert(String::from("KEY_3"),51);
+            default_codes.insert(String::from("KEY_4"),52);
+            default_codes.insert(String::from("KEY_5"),53);
+            default_codes.insert(String::from("KEY_6"),54);
+            default_codes.insert(String::from("KEY_7"),55);
+            default_codes.insert(String::from("KEY_8"),56);
+            default_codes.insert(String::from("KEY_9"),57);
+
+            for (key, code) in default_codes {
+                keycode_map.insert(key.clone(), KeyMapping::new(&key, code));
+            }
 
             println!("Default keymap assigned");
         }
 
         if let Ok(json_file) = read_platform_config_json("/opt/dab_platform_keymap.json") {
-            match serde_json::from_str::<HashMap<String, u16>>(&json_file) {
+            match serde_json::from_str::<HashMap<String, KeyMappingConfig>>(&json_file) {
                 Ok(new_keymap) => {
-                    for (key, value) in new_keymap {
-                        keycode_map.insert(key, value);
-                    }
+                    merge_keymap(&mut keycode_map, new_keymap);
                     println!("Added keymap from /opt/dab_platform_keymap.json");
                 },
                 Err(error) => {
@@ -555,8 +1157,100 @@ lazy_static! {
                 }
             }
         }
+
         keycode_map
     };
+
+    // Platform profiles are tried before the image ones.
+    static ref KEYMAP_PROFILES: Vec<KeymapProfile> = {
+        let mut profiles = read_keymap_profiles("/opt/dab_platform_keymap_profiles.json");
+        profiles.append(&mut read_keymap_profiles("/etc/dab/keymap_profiles.json"));
+        profiles
+    };
+}
+
+// The keymap with the device's profile applied. Only set once the profiles could be matched.
+static RDK_KEYMAP: OnceLock<HashMap<String, KeyMapping>> = OnceLock::new();
+
+// Returns the device properties the profiles match on, fetched from Thunder. Fails only when
+// Thunder can't be reached; properties Thunder can't tell are left out, so profiles using them
+// don't match.
+fn read_profile_device_info(profiles: &[KeymapProfile]) -> Result<HashMap<String, String>, DabError> {
+    let mut device = HashMap::new();
+    let mut unknown = HashSet::new();
+    for property in profiles.iter().flat_map(|profile| profile.device.keys()) {
+        if device.contains_key(property) || unknown.contains(property) {
+            continue;
+        }
+        match query_device_property(property)? {
+            Ok(Some(value)) => {
+                device.insert(property.clone(), value);
+            }
+            Ok(None) => {
+                eprintln!("Keymap profiles can't match on the unknown property {}.", property);
+                unknown.insert(property);
+            }
+            Err(err) => {
+                eprintln!("Keymap profiles can't match on {}, which Thunder can't tell: {:?}", property, err);
+                unknown.insert(property);
+            }
+        }
+    }
+    Ok(device)
+}
+
+fn apply_keymap_profile(
+    base: &HashMap<String, KeyMapping>,
+    profiles: &[KeymapProfile],
+    device: &HashMap<String, String>,
+) -> HashMap<String, KeyMapping> {
+    let mut keycode_map = base.clone();
+    if let Some(profile) = profiles.iter().find(|profile| profile.matches_device(device)) {
+        println!("Applying keymap profile {}", profile.name);
+        merge_keymap(&mut keycode_map, profile.keys.clone());
+    }
+    keycode_map
+}
+
+// Until Thunder can be reached for the device info, the keymap without profiles is used and the
+// profiles are matched again on the next call.
+fn rdk_keymap() -> &'static HashMap<String, KeyMapping> {
+    if let Some(keymap) = RDK_KEYMAP.get() {
+        return keymap;
+    }
+    match read_profile_device_info(&KEYMAP_PROFILES) {
+        Ok(device) => RDK_KEYMAP.get_or_init(|| apply_keymap_profile(&BASE_KEYMAP, &KEYMAP_PROFILES, &device)),
+        Err(err) => {
+            eprintln!("Unable to match keymap profiles yet: {:?}", err);
+            &BASE_KEYMAP
+        }
+    }
+}
+
+
+// Reads a DeviceInfo property from Thunder. Returns None for properties the adapter doesn't know.
+fn read_device_property(property: &str) -> Result<Option<String>, DabError> {
+    query_device_property(property)?
+}
+
+// Like read_device_property, but the outer error means Thunder couldn't be reached and the inner
+// one that Thunder answered without the property.
+fn query_device_property(property: &str) -> Result<Result<Option<String>, DabError>, DabError> {
+    let value = match property {
+        "manufacturer" => query_thunder_property("DeviceInfo.make", "make")?,
+        "model" => query_thunder_property("DeviceInfo.modelid", "sku")?,
+        "serialnumber" => query_thunder_property("DeviceInfo.serialnumber", "serialnumber")?,
+        "chipset" => match query_thunder_property("DeviceInfo.socname", "socname")? {
+            Ok(value) => Ok(value),
+            Err(_) => {
+                eprintln!("Unable to retrieve chipset from DeviceInfo, trying legacy DeviceIdentification.");
+                query_thunder_property("DeviceIdentification.deviceidentification", "chipset")?
+            }
+        },
+        "firmwareversion" => query_thunder_property("DeviceInfo.firmwareversion", "imagename")?,
+        _ => return Ok(Ok(None)),
+    };
+    Ok(value.map(Some))
 }
 
 // Static device info; no need to panic or break runtime. Implementation is based on the assumption
@@ -564,52 +1258,23 @@ lazy_static! {
 lazy_static! {
     static ref RDK_DEVICE_INFO: HashMap<String, String> = {
         let mut rdk_device_info = HashMap::new();
-        match get_thunder_property("DeviceInfo.make", "make") {
-            Ok(make) => { rdk_device_info.insert(String::from("manufacturer"), String::from(make)); },
-            Err(_err) => {
-                if cfg!(debug_assertions) {
-                    rdk_device_info.insert(String::from("manufacturer"), String::from("Unknown-manufacturer"));
-                }
-            },
-        };
-        match get_thunder_property("DeviceInfo.modelid", "sku") {
-            Ok(model) => { rdk_device_info.insert(String::from("model"), String::from(model)); },
-            Err(_err) => { 
-                if cfg!(debug_assertions) {
-                    rdk_device_info.insert(String::from("model"), String::from("Unknown-model"));
-                }
-            },
-        };
-        match get_thunder_property("DeviceInfo.serialnumber", "serialnumber") {
-            Ok(serialnumber) => { rdk_device_info.insert(String::from("serialnumber"), String::from(serialnumber)); },
-            Err(_err) => {
-                if cfg!(debug_assertions) {
-                    rdk_device_info.insert(String::from("serialnumber"), String::from("Unknown-serialnumber"));
-                }
-            },
-        };
-        match get_thunder_property("DeviceInfo.socname", "socname") {
-            Ok(socname) => { rdk_device_info.insert(String::from("chipset"), String::from(socname)); },
-            Err(_err) => {
-                eprintln!("Unable to retrieve chipset from DeviceInfo, trying legacy DeviceIdentification.");
-                match get_thunder_property("DeviceIdentification.deviceidentification", "chipset") {
-                    Ok(chipset) => { rdk_device_info.insert(String::from("chipset"), String::from(chipset)); },
-                    Err(_err) => {
-                        if cfg!(debug_assertions) {
-                            rdk_device_info.insert(String::from("chipset"), String::from("Unknown-chipset"));
-                        }
-                    },
-                };
-            },
-        };
-        match get_thunder_property("DeviceInfo.firmwareversion", "imagename") {
-            Ok(firmwareversion) => { rdk_device_info.insert(String::from("firmwareversion"), String::from(firmwareversion)); },
-            Err(_err) => {
-                if cfg!(debug_assertions) {
-                    rdk_device_info.insert(String::from("firmwareversion"), String::from("Unknown-FWVersion"));
-                }
-            },
-        };
+        let properties = [
+            ("manufacturer", "Unknown-manufacturer"),
+            ("model", "Unknown-model"),
+            ("serialnumber", "Unknown-serialnumber"),
+            ("chipset", "Unknown-chipset"),
+            ("firmwareversion", "Unknown-FWVersion"),
+        ];
+        for (property, placeholder) in properties {
+            match read_device_property(property) {
+                Ok(Some(value)) => { rdk_device_info.insert(String::from(property), value); },
+                _ => {
+                    if cfg!(debug_assertions) {
+                        rdk_device_info.insert(String::from(property), String::from(placeholder));
+                    }
+                },
+            };
+        }
         rdk_device_info
     };
 }
@@ -630,15 +1295,39 @@ pub fn get_ip_address() -> String {
     DEVICE_ADDRESS.get().expect("init() not called").clone()
 }
 
+// Returns the keys mapped to an RDK key code.
 pub fn get_rdk_keys() -> Vec<String> {
-    RDK_KEYMAP
-        .keys()
-        .map(|k| k.to_owned().to_string())
+    rdk_keymap()
+        .iter()
+        .filter(|(_, mapping)| mapping.code != 0)
+        .map(|(k, _)| k.to_owned().to_string())
         .collect()
 }
 
 pub fn get_keycode(keyname: String) -> Option<&'static u16> {
-    RDK_KEYMAP.get(&keyname)
+    rdk_keymap()
+        .get(&keyname)
+        .map(|mapping| &mapping.code)
+        .filter(|code| **code != 0)
+}
+
+// Returns the DAB key mapped to the RDK key code; the first in alphabetical order when several are.
+pub fn get_key_name(code: u16) -> Option<String> {
+    if code == 0 {
+        return None;
+    }
+    rdk_keymap()
+        .iter()
+        .filter(|(_, mapping)| mapping.code == code)
+        .map(|(key, _)| key.clone())
+        .min()
+}
+
+pub fn get_key_injection(keyname: &str) -> KeyInjection {
+    rdk_keymap()
+        .get(keyname)
+        .map(|mapping| mapping.injection)
+        .unwrap_or_else(|| default_key_injection(keyname))
 }
 
 pub fn rdk_sound_mode_to_dab(mode: &String) -> Option<AudioOutputMode> {
@@ -659,10 +1348,7 @@ pub fn rdk_sound_mode_to_dab(mode: &String) -> Option<AudioOutputMode> {
 // Telemetry operations
 
 pub fn get_device_memory() -> Result<u32, DabError> {
-    // Both properties are in bytes; convert to KB for DAB.
-    let free_ram_bytes = get_thunder_property("DeviceInfo.systeminfo", "freeram")?;
-    let free_ram_bytes = free_ram_bytes.parse::<u32>()
-        .map_err(|_| DabError::Err500("Failed to parse free RAM".to_string()))? / 1024;
+    let free_ram_bytes = get_device_free_memory()?;
 
     let total_ram_bytes = get_thunder_property("DeviceInfo.systeminfo", "totalram")?;
     let total_ram_bytes = total_ram_bytes.parse::<u32>()
@@ -671,6 +1357,16 @@ pub fn get_device_memory() -> Result<u32, DabError> {
     Ok(total_ram_bytes - free_ram_bytes)
 }
 
+// Returns the free memory in KB.
+pub fn get_device_free_memory() -> Result<u32, DabError> {
+    // The property is in bytes; convert to KB for DAB.
+    let free_ram_bytes = get_thunder_property("DeviceInfo.systeminfo", "freeram")?;
+    let free_ram_bytes = free_ram_bytes.parse::<u32>()
+        .map_err(|_| DabError::Err500("Failed to parse free RAM".to_string()))? / 1024;
+
+    Ok(free_ram_bytes)
+}
+
 pub fn get_device_cpu() -> Result<u32, DabError> {
     let cpu_usage = get_thunder_property("DeviceInfo.systeminfo", "cpuload")?;
     let cpu_usage = cpu_usage.parse::<u32>()
@@ -679,6 +1375,151 @@ pub fn get_device_cpu() -> Result<u32, DabError> {
     Ok(cpu_usage)
 }
 
+// ############################### App Telemetry ###############################
+
+// Returns the resident memory of the app in KB, from the Monitor plugin or, when running on the
+// device, from /proc.
+pub fn get_app_memory(app_id: &str) -> Result<u32, DabError> {
+    get_client_memory(&get_app(app_id).callsign())
+}
+
+// Returns the resident memory in KB of the plugin with the given callsign.
+pub fn get_client_memory(callsign: &str) -> Result<u32, DabError> {
+    match get_monitor_resident_memory(callsign) {
+        Ok(memory) => Ok(memory),
+        Err(err) => {
+            if !is_local_device() {
+                return Err(err);
+            }
+            let pid = find_app_pid(callsign)?;
+            let status = std::fs::read_to_string(format!("/proc/{}/status", pid))
+                .map_err(|e| DabError::Err500(e.to_string()))?;
+            status
+                .lines()
+                .find(|line| line.starts_with("VmRSS:"))
+                .and_then(|line| line.split_whitespace().nth(1))
+                .and_then(|kb| kb.parse::<u32>().ok())
+                .ok_or(DabError::Err500(format!("No VmRSS for process {}", pid)))
+        }
+    }
+}
+
+fn get_monitor_resident_memory(callsign: &str) -> Result<u32, DabError> {
+    let response: Value = rdk_request(&format!("Monitor.1.status@{}", callsign))?;
+    // Measurements are reported in bytes.
+    let resident = response["result"]
+        .as_array()
+        .and_then(|list| list.first())
+        .and_then(|measurement| measurement["measurements"]["resident"]["last"].as_u64())
+        .ok_or(DabError::Err500(format!("Monitor has no measurements for {}", callsign)))?;
+    Ok((resident / 1024) as u32)
+}
+
+lazy_static! {
+    // Last process and its (process, total) CPU ticks seen for each app, keyed by appId.
+    static ref APP_CPU_SAMPLES: Mutex<HashMap<String, (u32, (u64, u64))>> = Mutex::new(HashMap::new());
+}
+
+// Whether the app memory can be read on this setup: from the Monitor plugin, or from /proc on the
+// device.
+pub fn app_memory_available() -> bool {
+    is_local_device() || thunder_plugin_exists("Monitor")
+}
+
+// Whether the app CPU usage can be read on this setup; it only comes from /proc on the device.
+pub fn app_cpu_available() -> bool {
+    is_local_device()
+}
+
+// Returns the share of the CPU time (in percent) used by the app since the previous call. Only
+// available when running on the device.
+pub fn get_app_cpu(app_id: &str) -> Result<u32, DabError> {
+    if !app_cpu_available() {
+        return Err(DabError::Err501(
+            "App CPU usage is only available when running on the device".to_string(),
+        ));
+    }
+
+    let pid = find_app_pid(&get_app(app_id).callsign())?;
+
+    // A sample of an earlier process of the app says nothing about this one.
+    let previous = APP_CPU_SAMPLES.lock().unwrap().get(app_id).copied();
+    let previous = match previous {
+        Some((sample_pid, sample)) if sample_pid == pid => sample,
+        _ => {
+            // First sample of this process; measure over a short interval.
+            let sample = read_cpu_ticks(pid)?;
+            thread::sleep(time::Duration::from_millis(250));
+            sample
+        }
+    };
+
+    let current = read_cpu_ticks(pid)?;
+    APP_CPU_SAMPLES.lock().unwrap().insert(app_id.to_string(), (pid, current));
+
+    let process_ticks = current.0.saturating_sub(previous.0);
+    let total_ticks = current.1.saturating_sub(previous.1);
+    if total_ticks == 0 {
+        return Ok(0);
+    }
+    Ok((process_ticks * 100 / total_ticks) as u32)
+}
+
+// Forgets the last CPU sample of the app, once its telemetry stops.
+pub fn forget_app_cpu(app_id: &str) {
+    APP_CPU_SAMPLES.lock().unwrap().remove(app_id);
+}
+
+// Returns the CPU ticks used by the process and by the whole system.
+fn read_cpu_ticks(pid: u32) -> Result<(u64, u64), DabError> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid))
+        .map_err(|e| DabError::Err500(e.to_string()))?;
+    // Fields following the command name, which may contain spaces; utime and stime are the 14th
+    // and 15th fields of the line.
+    let fields: Vec<&str> = stat
+        .rsplit_once(')')
+        .map(|(_, rest)| rest.split_whitespace().collect())
+        .unwrap_or_default();
+    let field = |index: usize| fields.get(index).and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+    let process_ticks = field(11) + field(12);
+
+    let system_stat = std::fs::read_to_string("/proc/stat")
+        .map_err(|e| DabError::Err500(e.to_string()))?;
+    let total_ticks = system_stat
+        .lines()
+        .next()
+        .unwrap_or("")
+        .split_whitespace()
+        .skip(1)
+        .filter_map(|v| v.parse::<u64>().ok())
+        .sum();
+
+    Ok((process_ticks, total_ticks))
+}
+
+// Finds the process hosting the plugin; out-of-process plugins run as "WPEProcess ... -C <callsign>".
+fn find_app_pid(callsign: &str) -> Result<u32, DabError> {
+    let entries = std::fs::read_dir("/proc").map_err(|e| DabError::Err500(e.to_string()))?;
+
+    for entry in entries.flatten() {
+        let Ok(pid) = entry.file_name().to_string_lossy().parse::<u32>() else {
+            continue;
+        };
+        let Ok(cmdline) = std::fs::read(format!("/proc/{}/cmdline", pid)) else {
+            continue;
+        };
+        let args: Vec<String> = cmdline
+            .split(|b| *b == 0)
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+        if args.windows(2).any(|w| w[0] == "-C" && w[1].eq_ignore_ascii_case(callsign)) {
+            return Ok(pid);
+        }
+    }
+
+    Err(DabError::Err500(format!("No process found for {}", callsign)))
+}
+
 // Read platform override JSON configs from file
 // Optional override configuration; do not panic or break runtime.
 pub fn read_platform_config_json(file_path: &str) -> Result<String, DabError> {
@@ -702,9 +1543,19 @@ pub fn read_platform_config_json(file_path: &str) -> Result<String, DabError> {
 // Parameters: method_name: The method name to call, key_name: The key to be matched in the response.
 // Returns the value of the key as String on success else DabError.
 pub fn get_thunder_property(method_name: &str, key_name: &str) -> Result<String, DabError> {
+    query_thunder_property(method_name, key_name)?
+}
+
+// Like get_thunder_property, but the outer error means Thunder couldn't be reached and the inner
+// one that Thunder answered without the property.
+fn query_thunder_property(method_name: &str, key_name: &str) -> Result<Result<String, DabError>, DabError> {
     let json_string = format!("{{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"{}\"}}", method_name);
     let response = http_post(json_string)?;
-    let response_value: serde_json::Value = serde_json::from_str(&response).map_err(|e| DabError::Err500(format!("Failed to parse response: {}", e)))?;
+    Ok(parse_thunder_property(&response, method_name, key_name))
+}
+
+fn parse_thunder_property(response: &str, method_name: &str, key_name: &str) -> Result<String, DabError> {
+    let response_value: serde_json::Value = serde_json::from_str(response).map_err(|e| DabError::Err500(format!("Failed to parse response: {}", e)))?;
     let result = response_value.get("result").ok_or(DabError::Err500(format!("Key 'result' not found in response for method '{}'.", method_name)))?;
     if result.is_null() {
         return Err(DabError::Err500(format!("Key 'result' is null in response for method '{}'.", method_name)));
@@ -734,43 +1585,61 @@ pub fn get_thunder_property(method_name: &str, key_name: &str) -> Result<String,
 type TimeoutMap = HashMap<String, u64>;
 type LifecycleTimeouts = HashMap<String, TimeoutMap>;
 
+pub const LIFECYCLE_TIMEOUT_TYPES: [&str; 4] = [
+    "cold_launch_timeout_ms",
+    "resume_launch_timeout_ms",
+    "exit_to_destroy_timeout_ms",
+    "exit_to_background_timeout_ms",
+];
+
+// Section of the platform file applying to every app without its own value.
+pub const DEFAULT_LIFECYCLE_SECTION: &str = "default";
+const DEFAULT_LIFECYCLE_TIMEOUT_MS: u64 = 2500;
+
 lazy_static! {
     static ref APP_LIFECYCLE_TIMEOUTS: LifecycleTimeouts = {
+        // Built-in defaults are part of the app catalog; this file overrides them.
         let mut app_lifecycle_timeouts = LifecycleTimeouts::new();
 
-        app_lifecycle_timeouts.insert("youtube".to_string(), {
-            let mut timeouts = TimeoutMap::new();
-            timeouts.insert("cold_launch_timeout_ms".to_string(), 6000);
-            timeouts.insert("resume_launch_timeout_ms".to_string(), 3000);
-            timeouts.insert("exit_to_destroy_timeout_ms".to_string(), 2500);
-            timeouts.insert("exit_to_background_timeout_ms".to_string(), 2000);
-            timeouts
-        });
-
         match read_platform_config_json("/opt/dab_platform_app_lifecycle.json") {
             /* File Format Reference:
                 {
+                    "default": {
+                        "cold_launch_timeout_ms": 4000
+                    },
                     "youtube": {
                         "cold_launch_timeout_ms": 6000,
                         "resume_launch_timeout_ms": 3000,
                         "exit_to_destroy_timeout_ms": 2500,
                         "exit_to_background_timeout_ms": 2000
                     },
-                    "netflix": {
-                        "cold_launch_timeout_ms": 6000,
-                        "resume_launch_timeout_ms": 3000,
-                        "exit_to_destroy_timeout_ms": 2500,
-                        "exit_to_background_timeout_ms": 2000
+                    "com.example.myapp": {
+                        "cold_launch_timeout_ms": 8000
                     }
                 }
             */
             Ok(json_file) => {
-                match serde_json::from_str::<HashMap<String, HashMap<String, u64>>>(&json_file) {
+                match serde_json::from_str::<HashMap<String, HashMap<String, Value>>>(&json_file) {
                     Ok(app_lifecycle_config) => {
                         for (app_id, timeout_map) in app_lifecycle_config {
-                            if app_id == "youtube" || app_id == "uk.co.bbc.iplayer" || app_id == "netflix" || app_id == "primevideo" {
-                                app_lifecycle_timeouts.insert(app_id, timeout_map);
+                            let mut timeouts = TimeoutMap::new();
+                            for (key, value) in timeout_map {
+                                if !LIFECYCLE_TIMEOUT_TYPES.contains(&key.as_str()) {
+                                    eprintln!("Ignoring unknown lifecycle timeout '{}' of '{}' in 'dab_platform_app_lifecycle.json'; expected one of {:?}.",
+                                        key, app_id, LIFECYCLE_TIMEOUT_TYPES);
+                                    continue;
+                                }
+                                match value.as_u64() {
+                                    Some(timeout) => {
+                                        timeouts.insert(key, timeout);
+                                    }
+                                    None => {
+                                        eprintln!("Ignoring lifecycle timeout '{}' of '{}' in 'dab_platform_app_lifecycle.json'; {} is not a number of milliseconds.",
+                                            key, app_id, value);
+                                    }
+                                }
                             }
+                            app_lifecycle_timeouts.insert(app_id.to_lowercase(), timeouts);
                         }
                         println!("Imported platform specified app lifetime configuration file also.");
                     }
@@ -780,7 +1649,7 @@ lazy_static! {
                 }
             }
             Err(_) => {
-                println!("Using default values for app lifecycle timeouts.");
+                println!("Using app catalog values for app lifecycle timeouts.");
             }
         }
 
@@ -788,15 +1657,64 @@ lazy_static! {
     };
 }
 
-// Function to get lifecycle timeout for an app. After plugin state change how long App implementation/SDK takes to complete the action.
-// Parameters: app_name: The app name (lowercase) to get the timeout for, timeout_type: The type of timeout to get.
-// Returns the timeout in milliseconds on success else default 2500.
-pub fn get_lifecycle_timeout(app_name: &str, timeout_type: &str) -> Option<u64> {
+// Where an effective lifecycle timeout comes from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LifecycleTimeoutSource {
+    // Per-app value of /opt/dab_platform_app_lifecycle.json.
+    Platform,
+    Catalog,
+    // "default" section of /opt/dab_platform_app_lifecycle.json.
+    PlatformDefault,
+    BuiltIn,
+}
+
+impl LifecycleTimeoutSource {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            LifecycleTimeoutSource::Platform => "platform",
+            LifecycleTimeoutSource::Catalog => "catalog",
+            LifecycleTimeoutSource::PlatformDefault => "platform-default",
+            LifecycleTimeoutSource::BuiltIn => "built-in",
+        }
+    }
+}
+
+// Returns the timeout in milliseconds from the platform file, else from the app catalog, else from
+// the platform file "default" section, else default 2500.
+pub fn get_lifecycle_timeout(app_name: &str, timeout_type: &str) -> u64 {
+    get_lifecycle_timeout_with_source(app_name, timeout_type).0
+}
+
+pub fn get_lifecycle_timeout_with_source(app_name: &str, timeout_type: &str) -> (u64, LifecycleTimeoutSource) {
+    let platform = APP_LIFECYCLE_TIMEOUTS
+        .get(&app_name.to_lowercase())
+        .and_then(|timeouts| timeouts.get(timeout_type));
+
+    if let Some(timeout) = platform {
+        return (*timeout, LifecycleTimeoutSource::Platform);
+    }
+    if let Some(timeout) = get_app(app_name).lifecycle_timeouts.get(timeout_type) {
+        return (*timeout, LifecycleTimeoutSource::Catalog);
+    }
+    get_default_lifecycle_timeout(timeout_type)
+}
+
+// Returns the timeout of apps without a value of their own.
+pub fn get_default_lifecycle_timeout(timeout_type: &str) -> (u64, LifecycleTimeoutSource) {
     APP_LIFECYCLE_TIMEOUTS
-        .get(app_name)
+        .get(DEFAULT_LIFECYCLE_SECTION)
         .and_then(|timeouts| timeouts.get(timeout_type))
+        .map(|timeout| (*timeout, LifecycleTimeoutSource::PlatformDefault))
+        .unwrap_or((DEFAULT_LIFECYCLE_TIMEOUT_MS, LifecycleTimeoutSource::BuiltIn))
+}
+
+// Returns the apps with lifecycle timeouts in the platform file, the "default" section excluded.
+pub fn get_lifecycle_configured_apps() -> Vec<String> {
+    APP_LIFECYCLE_TIMEOUTS
+        .keys()
+        .filter(|app| app.as_str() != DEFAULT_LIFECYCLE_SECTION)
         .cloned()
-        .or_else(|| Some(2500))
+        .collect()
 }
 
 pub fn get_supported_languages() -> Vec<String> {
@@ -812,3 +1730,194 @@ pub fn get_audio_volume_range() -> AudioVolume {
         .clone()
         .unwrap_or_else(|| AudioVolume { min: 0, max: 100 })
 }
+
+pub fn get_launch_policy() -> Option<LaunchPolicySettings> {
+    SETTINGS.launch_policy.clone()
+}
+
+pub fn get_lifecycle_metrics_enabled() -> bool {
+    SETTINGS.lifecycle_metrics.unwrap_or(false)
+}
+
+pub fn get_key_hold_settings() -> KeyHoldSettings {
+    SETTINGS.key_hold.clone().unwrap_or_default()
+}
+
+pub fn get_screenshot_upload_settings() -> ScreenshotUploadSettings {
+    SETTINGS.screenshot_upload.clone().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::rdk::mock_thunder::MockThunder;
+
+    fn profiles() -> Vec<KeymapProfile> {
+        serde_json::from_value(json!([
+            {
+                "name": "acme-x1",
+                "match": { "manufacturer": "Acme", "model": "X1*" },
+                "keys": {
+                    "KEY_GUIDE": 458,
+                    "KEY_MUTE": { "code": 173, "injection": "inject_key" },
+                    "KEY_FAST_FORWARD": 0
+                }
+            },
+            {
+                "name": "acme",
+                "match": { "manufacturer": "acme" },
+                "keys": { "KEY_GUIDE": 459 }
+            }
+        ]))
+        .unwrap()
+    }
+
+    fn device(manufacturer: &str, model: &str) -> HashMap<String, String> {
+        HashMap::from([
+            ("manufacturer".to_string(), manufacturer.to_string()),
+            ("model".to_string(), model.to_string()),
+        ])
+    }
+
+    fn base_keymap() -> HashMap<String, KeyMapping> {
+        ["KEY_MUTE", "KEY_FAST_FORWARD", "KEY_ENTER"]
+            .iter()
+            .zip([173, 223, 13])
+            .map(|(key, code)| (key.to_string(), KeyMapping::new(key, code)))
+            .collect()
+    }
+
+    #[test]
+    fn keymap_profiles_parse() {
+        let profiles = profiles();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].name, "acme-x1");
+        assert_eq!(profiles[0].device["model"], "X1*");
+        assert!(matches!(profiles[0].keys["KEY_GUIDE"], KeyMappingConfig::Code(458)));
+        assert!(matches!(
+            profiles[0].keys["KEY_MUTE"],
+            KeyMappingConfig::Mapping { code: 173, injection: Some(KeyInjection::InjectKey) }
+        ));
+        assert!(serde_json::from_value::<Vec<KeymapProfile>>(json!([{ "match": {} }])).is_err());
+    }
+
+    #[test]
+    fn keymap_profiles_match_ignoring_case_with_prefix_patterns() {
+        let profiles = profiles();
+        assert!(profiles[0].matches_device(&device("ACME", "x1-pro")));
+        assert!(!profiles[0].matches_device(&device("Acme", "X2")));
+        assert!(!profiles[0].matches_device(&HashMap::from([("manufacturer".to_string(), "Acme".to_string())])));
+        assert!(profiles[1].matches_device(&device("Acme", "X2")));
+    }
+
+    #[test]
+    fn first_matching_keymap_profile_is_applied() {
+        let keymap = apply_keymap_profile(&base_keymap(), &profiles(), &device("Acme", "X1"));
+        assert_eq!(keymap["KEY_GUIDE"].code, 458);
+        assert_eq!(keymap["KEY_MUTE"].injection, KeyInjection::InjectKey);
+        assert_eq!(keymap["KEY_FAST_FORWARD"].code, 0);
+        assert_eq!(keymap["KEY_ENTER"].code, 13);
+
+        let keymap = apply_keymap_profile(&base_keymap(), &profiles(), &device("Other", "X1"));
+        assert!(!keymap.contains_key("KEY_GUIDE"));
+        assert_eq!(keymap["KEY_MUTE"].injection, KeyInjection::GenerateKey);
+    }
+
+    #[test]
+    fn keymap_profile_device_info_comes_from_thunder() {
+        let thunder = MockThunder::start(&["DeviceInfo"], |method, _, _| match method {
+            "DeviceInfo.make" => Ok(json!({ "make": "Acme" })),
+            "DeviceInfo.modelid" => Ok(json!({ "sku": "X1-Pro" })),
+            _ => Err("Unknown method".to_string()),
+        });
+
+        let mut profiles = profiles();
+        profiles[1].device.insert("region".to_string(), "eu".to_string());
+        let device = read_profile_device_info(&profiles).unwrap();
+        assert_eq!(device, self::device("Acme", "X1-Pro"));
+        assert_eq!(thunder.calls("DeviceInfo.make").len(), 1);
+    }
+
+    #[test]
+    fn keymap_profile_device_info_skips_properties_thunder_cant_tell() {
+        let thunder = MockThunder::start(&["DeviceInfo"], |method, _, _| match method {
+            "DeviceInfo.make" => Ok(json!({ "make": "Acme" })),
+            _ => Err("Unknown method".to_string()),
+        });
+
+        let mut profiles = profiles();
+        profiles[0].device.insert("chipset".to_string(), "bcm*".to_string());
+        let device = read_profile_device_info(&profiles).unwrap();
+        assert_eq!(device, HashMap::from([("manufacturer".to_string(), "Acme".to_string())]));
+        assert_eq!(thunder.calls("DeviceInfo.socname").len(), 1);
+        assert_eq!(thunder.calls("DeviceIdentification.deviceidentification").len(), 1);
+
+        // Profiles that don't use the unknown property still match.
+        let keymap = apply_keymap_profile(&base_keymap(), &profiles, &device);
+        assert_eq!(keymap["KEY_GUIDE"].code, 459);
+    }
+
+    #[test]
+    fn keymap_profile_device_info_fails_while_thunder_is_down() {
+        let _thunder = MockThunder::start(&["DeviceInfo"], |_, _, _| Err("Unknown method".to_string()));
+        // Nothing listens on port 1.
+        use_test_thunder(Some("127.0.0.1:1".to_string()));
+
+        assert!(read_profile_device_info(&profiles()).is_err());
+        assert!(read_profile_device_info(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn persisted_device_id_is_only_used_for_its_source() {
+        let path = std::env::temp_dir().join(format!("dab_device_id_{}", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        persist_device_id(path, None, &DeviceIdSource::EstbMac, "aabbccddeeff");
+        assert_eq!(read_persisted_device_id(path, &DeviceIdSource::EstbMac).as_deref(), Some("aabbccddeeff"));
+        assert_eq!(read_persisted_device_id(path, &DeviceIdSource::SerialNumber), None);
+
+        // IDs persisted without their source are ignored.
+        std::fs::write(path, "aabbccddeeff").unwrap();
+        assert_eq!(read_persisted_device_id(path, &DeviceIdSource::EstbMac), None);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn thunder_methods_split_into_callsign_and_method() {
+        assert_eq!(split_thunder_method("org.rdk.System.getDeviceInfo"), Some(("org.rdk.System", "getDeviceInfo".to_string())));
+        assert_eq!(split_thunder_method("org.rdk.System.2.getDeviceInfo"), Some(("org.rdk.System", "getDeviceInfo".to_string())));
+        assert_eq!(split_thunder_method("Controller.1.status@DeviceInfo"), Some(("Controller", "status@DeviceInfo".to_string())));
+        assert_eq!(split_thunder_method("Monitor.1.status@org.rdk.RDKShell"), Some(("Monitor", "status@org.rdk.RDKShell".to_string())));
+        assert_eq!(split_thunder_method("status"), None);
+    }
+
+    #[test]
+    fn thunder_methods_follow_the_reported_plugin_versions() {
+        let _thunder = MockThunder::start(&["org.rdk.System"], |_, _, _| Err("Unknown method".to_string()));
+
+        // Plugins without configured versions are called as requested.
+        assert_eq!(thunder_methods("DeviceInfo.1.make"), ["DeviceInfo.1.make"]);
+        // Designators are kept.
+        assert_eq!(thunder_methods("Controller.status@DeviceInfo"), ["Controller.1.status@DeviceInfo"]);
+
+        // Without a reported version every known version is tried, newest first.
+        assert_eq!(
+            thunder_methods("org.rdk.System.getDeviceInfo"),
+            ["org.rdk.System.2.getDeviceInfo", "org.rdk.System.1.getDeviceInfo"]
+        );
+
+        *PLUGIN_VERSIONS.lock().unwrap() = Some(HashMap::from([
+            ("org.rdk.System".to_string(), Some(1)),
+            ("org.rdk.DisplaySettings".to_string(), Some(0)),
+            ("org.rdk.UserSettings".to_string(), Some(3)),
+        ]));
+        assert_eq!(thunder_methods("org.rdk.System.2.getDeviceInfo"), ["org.rdk.System.1.getDeviceInfo"]);
+        // Older than any known version: the oldest known one is tried.
+        assert_eq!(thunder_methods("org.rdk.DisplaySettings.getConnectedVideoDisplays"), ["org.rdk.DisplaySettings.1.getConnectedVideoDisplays"]);
+        assert_eq!(thunder_methods("org.rdk.UserSettings.getAudioDescription"), ["org.rdk.UserSettings.1.getAudioDescription"]);
+
+        // Once a version has answered, only that one is used.
+        remember_thunder_method("org.rdk.System.getFriendlyName", "org.rdk.System.2.getFriendlyName");
+        assert_eq!(thunder_methods("org.rdk.System.1.getFriendlyName"), ["org.rdk.System.2.getFriendlyName"]);
+    }
+}
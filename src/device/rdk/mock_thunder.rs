@@ -0,0 +1,233 @@
+// A Thunder stand-in for tests. It answers JSON-RPC requests over HTTP and WebSocket on a local
+// port, and sends the events the handler asks for to the WebSocket clients registered for them.
+// Controller.1.status lists the given plugins; every other method goes to the handler.
+
+use crate::device::rdk::interface::use_test_thunder;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::runtime::Runtime;
+use tokio::sync::broadcast;
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+type Handler = dyn Fn(&str, &Value, &Events) -> Result<Value, String> + Send + Sync;
+
+// Tests using a mock Thunder run one at a time, as the adapter talks to a single Thunder.
+static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Clone)]
+pub struct Events(broadcast::Sender<(String, Value)>);
+
+impl Events {
+    // Sends the event after `delay_ms`, which leaves the client time to register for it.
+    pub fn send_after(&self, delay_ms: u64, event: &str, params: Value) {
+        let sender = self.0.clone();
+        let event = event.to_string();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(delay_ms));
+            let _ = sender.send((event, params));
+        });
+    }
+}
+
+struct State {
+    plugins: Vec<Value>,
+    handler: Box<Handler>,
+    calls: Arc<Mutex<Vec<(String, Value)>>>,
+    events: Events,
+}
+
+impl State {
+    fn answer(&self, request: &Value) -> Value {
+        let method = request["method"].as_str().unwrap_or_default();
+        let params = request["params"].clone();
+        self.calls.lock().unwrap().push((method.to_string(), params.clone()));
+
+        let reply = if method == "Controller.1.status" {
+            Ok(Value::Array(self.plugins.clone()))
+        } else if method.ends_with(".register") || method.ends_with(".unregister") {
+            Ok(json!(0))
+        } else {
+            (self.handler)(method, &params, &self.events)
+        };
+
+        match reply {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+            Err(message) => json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "error": { "code": -32601, "message": message }
+            }),
+        }
+    }
+}
+
+pub struct MockThunder {
+    calls: Arc<Mutex<Vec<(String, Value)>>>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl MockThunder {
+    pub fn start<F>(plugins: &[&str], handler: F) -> MockThunder
+    where
+        F: Fn(&str, &Value, &Events) -> Result<Value, String> + Send + Sync + 'static,
+    {
+        let lock = TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        let calls = Arc::new(Mutex::new(vec![]));
+        let (events, _) = broadcast::channel(64);
+        let state = Arc::new(State {
+            plugins: plugins
+                .iter()
+                .map(|callsign| json!({ "callsign": callsign, "state": "activated" }))
+                .collect(),
+            handler: Box::new(handler),
+            calls: calls.clone(),
+            events: Events(events),
+        });
+
+        thread::spawn(move || {
+            Runtime::new().unwrap().block_on(async move {
+                let listener = TcpListener::from_std(listener).unwrap();
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(serve(stream, state.clone()));
+                }
+            });
+        });
+
+        use_test_thunder(Some(address));
+        MockThunder { calls, _lock: lock }
+    }
+
+    // Returns the params of every call to `method`.
+    pub fn calls(&self, method: &str) -> Vec<Value> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(m, _)| m == method)
+            .map(|(_, params)| params.clone())
+            .collect()
+    }
+}
+
+impl Drop for MockThunder {
+    fn drop(&mut self) {
+        use_test_thunder(None);
+    }
+}
+
+async fn serve(stream: TcpStream, state: Arc<State>) {
+    // Peek at the headers to tell WebSocket upgrades from plain requests.
+    let mut buf = [0u8; 4096];
+    let head = loop {
+        let n = stream.peek(&mut buf).await.unwrap_or(0);
+        let head = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+        if n == 0 || n == buf.len() || head.contains("\r\n\r\n") {
+            break head;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    };
+
+    if head.contains("upgrade: websocket") {
+        serve_websocket(stream, state).await;
+    } else {
+        serve_http(stream, state).await;
+    }
+}
+
+async fn serve_http(mut stream: TcpStream, state: Arc<State>) {
+    let mut data = vec![];
+    let mut buf = [0u8; 4096];
+    let body = loop {
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => data.extend_from_slice(&buf[..n]),
+        }
+        let Some(end) = data.windows(4).position(|w| w == b"\r\n\r\n") else {
+            continue;
+        };
+        let head = String::from_utf8_lossy(&data[..end]).to_lowercase();
+        let length = head
+            .lines()
+            .find_map(|line| line.strip_prefix("content-length:"))
+            .and_then(|value| value.trim().parse::<usize>().ok())
+            .unwrap_or(0);
+        if data.len() >= end + 4 + length {
+            break data[end + 4..end + 4 + length].to_vec();
+        }
+    };
+
+    let Ok(request) = serde_json::from_slice::<Value>(&body) else {
+        return;
+    };
+    let response = state.answer(&request).to_string();
+    let reply = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.len(),
+        response
+    );
+    let _ = stream.write_all(reply.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+async fn serve_websocket(stream: TcpStream, state: Arc<State>) {
+    let Ok(mut ws_stream) = tokio_tungstenite::accept_async(stream).await else {
+        return;
+    };
+    let mut events = state.events.0.subscribe();
+    // (event, designator) of each registration.
+    let mut registered: Vec<(String, String)> = vec![];
+
+    loop {
+        tokio::select! {
+            biased;
+            message = ws_stream.next() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(_)) => continue,
+                    _ => return,
+                };
+                let Ok(request) = serde_json::from_str::<Value>(&text) else {
+                    continue;
+                };
+                if request["method"].as_str().unwrap_or_default().ends_with(".register") {
+                    let params = &request["params"];
+                    registered.push((
+                        params["event"].as_str().unwrap_or_default().to_string(),
+                        params["id"].as_str().unwrap_or_default().to_string(),
+                    ));
+                }
+                let response = state.answer(&request);
+                if ws_stream.send(Message::Text(response.to_string())).await.is_err() {
+                    return;
+                }
+            }
+            event = events.recv() => {
+                let (event, params) = match event {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return,
+                };
+                for (_, designator) in registered.iter().filter(|(e, _)| *e == event) {
+                    let message = json!({
+                        "jsonrpc": "2.0",
+                        "method": format!("{}.{}", designator, event),
+                        "params": params
+                    });
+                    if ws_stream.send(Message::Text(message.to_string())).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
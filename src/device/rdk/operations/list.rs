@@ -27,6 +27,24 @@ pub fn process(_dab_request: OperationsListRequest) -> Result<String, DabError>
     ResponseOperator
         .operations
         .push("applications/exit".to_string());
+    ResponseOperator
+        .operations
+        .push("applications/install".to_string());
+    ResponseOperator
+        .operations
+        .push("applications/uninstall".to_string());
+    ResponseOperator
+        .operations
+        .push("applications/clear-data".to_string());
+    ResponseOperator
+        .operations
+        .push("applications/clients".to_string());
+    ResponseOperator
+        .operations
+        .push("applications/focus".to_string());
+    ResponseOperator
+        .operations
+        .push("applications/lifecycle-timeouts".to_string());
     ResponseOperator.operations.push("device/info".to_string());
     ResponseOperator
         .operations
@@ -49,6 +67,16 @@ pub fn process(_dab_request: OperationsListRequest) -> Result<String, DabError>
     ResponseOperator
         .operations
         .push("input/long-key-press".to_string());
+    ResponseOperator
+        .operations
+        .push("input/key-sequence".to_string());
+    ResponseOperator.operations.push("input/text".to_string());
+    ResponseOperator
+        .operations
+        .push("input/key-capture/start".to_string());
+    ResponseOperator
+        .operations
+        .push("input/key-capture/stop".to_string());
     ResponseOperator.operations.push("output/image".to_string());
     // ResponseOperator
     //     .operations
@@ -59,12 +87,12 @@ pub fn process(_dab_request: OperationsListRequest) -> Result<String, DabError>
     // ResponseOperator
     //     .operations
     //     .push("device-telemetry/metrics".to_string());
-    // ResponseOperator
-    //     .operations
-    //     .push("app-telemetry/start".to_string());
-    // ResponseOperator
-    //     .operations
-    //     .push("app-telemetry/stop".to_string());
+    ResponseOperator
+        .operations
+        .push("app-telemetry/start".to_string());
+    ResponseOperator
+        .operations
+        .push("app-telemetry/stop".to_string());
     ResponseOperator
         .operations
         .push("health-check/get".to_string());
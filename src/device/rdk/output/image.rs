@@ -2,55 +2,99 @@ use crate::dab::structs::CaptureScreenshotRequest;
 use crate::dab::structs::CaptureScreenshotResponse;
 use crate::dab::structs::DabError;
 use crate::device::rdk::interface::http_post;
+use crate::device::rdk::interface::get_screenshot_upload_settings;
 use crate::device::rdk::interface::{get_service_state, is_local_device, service_activate};
 use serde::Serialize;
 
 use base64::{engine::general_purpose, Engine as _};
 use bytes::Bytes;
 use crossbeam::channel::{self, Receiver, Sender};
+use ::image::imageops::FilterType;
+use ::image::{DynamicImage, ImageOutputFormat};
 use hyper::server::Server;
 use hyper::service::{make_service_fn, service_fn};
 use hyper::{Body, Request, Response};
 use hyper::{Method, StatusCode};
 use local_ip_address::local_ip;
 use std::convert::Infallible;
-use std::net::SocketAddr;
-use std::sync::OnceLock;
+use std::fs::File;
+use std::io::{Cursor, Read};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::thread;
 use tokio::runtime::Runtime;
 use tokio::time::Duration;
 
 struct UploadServer {
     rx: Receiver<Bytes>,
+    // Token of the upload the request in progress waits for.
+    token: Arc<Mutex<Option<String>>>,
+    addr: SocketAddr,
+}
+
+impl UploadServer {
+    fn expect_upload(&self, token: Option<String>) {
+        *self.token.lock().unwrap() = token;
+    }
 }
 
 static UPLOAD_SERVER: OnceLock<Result<UploadServer, DabError>> = OnceLock::new();
 
 fn ensure_upload_server() -> Result<&'static UploadServer, DabError> {
     match UPLOAD_SERVER.get_or_init(|| {
+        let settings = get_screenshot_upload_settings();
+        let ip = settings.bind_address.parse::<IpAddr>().map_err(|_| {
+            DabError::Err500(format!(
+                "Invalid screenshot upload bind address '{}'",
+                settings.bind_address
+            ))
+        })?;
+        let addr = SocketAddr::new(ip, settings.port);
+
         let (tx, rx) = channel::unbounded();
+        let (bound_tx, bound_rx) = channel::bounded(1);
+        let token = Arc::new(Mutex::new(None));
+        let server_token = token.clone();
 
         thread::Builder::new()
             .name("screen-capture-upload".to_string())
             .spawn(move || {
                 let rt = Runtime::new().expect("failed to create screenshot runtime");
                 rt.block_on(async move {
+                    let builder = match Server::try_bind(&addr) {
+                        Ok(builder) => builder,
+                        Err(err) => {
+                            let _ = bound_tx.send(Err(err.to_string()));
+                            return;
+                        }
+                    };
+
                     let make_svc = make_service_fn(move |_conn| {
                         let tx = tx.clone();
+                        let token = server_token.clone();
                         async move {
-                            Ok::<_, Infallible>(service_fn(move |req| handle_req(req, tx.clone())))
+                            Ok::<_, Infallible>(service_fn(move |req| {
+                                handle_req(req, tx.clone(), token.clone())
+                            }))
                         }
                     });
 
-                    let addr = SocketAddr::from(([0, 0, 0, 0], 7878));
-                    if let Err(err) = Server::bind(&addr).serve(make_svc).await {
+                    let server = builder.serve(make_svc);
+                    let _ = bound_tx.send(Ok(server.local_addr()));
+                    if let Err(err) = server.await {
                         eprintln!("screenshot upload server failed: {}", err);
                     }
                 });
             })
             .map_err(|e| DabError::Err500(format!("Failed to start upload server: {}", e)))?;
 
-        Ok(UploadServer { rx })
+        let addr = bound_rx
+            .recv()
+            .map_err(|_| DabError::Err500("The screenshot upload server failed to start".to_string()))?
+            .map_err(|e| DabError::Err500(format!("Failed to bind the upload server to {}: {}", addr, e)))?;
+        println!("Screenshot upload server listening on {}", addr);
+
+        Ok(UploadServer { rx, token, addr })
     }) {
         Ok(server) => Ok(server),
         Err(err) => Err(match err {
@@ -61,8 +105,12 @@ fn ensure_upload_server() -> Result<&'static UploadServer, DabError> {
     }
 }
 
-fn upload_url() -> Result<String, DabError> {
-    let host = if is_local_device() {
+fn upload_url(server: &UploadServer, token: &str) -> Result<String, DabError> {
+    let host = if let Some(host) = get_screenshot_upload_settings().advertised_host {
+        host
+    } else if !server.addr.ip().is_unspecified() {
+        server.addr.ip().to_string()
+    } else if is_local_device() {
         "127.0.0.1".to_string()
     } else {
         local_ip()
@@ -70,13 +118,159 @@ fn upload_url() -> Result<String, DabError> {
             .map_err(|e| DabError::Err500(format!("Failed to resolve local IP: {}", e)))?
     };
 
-    Ok(format!("http://{}:7878/upload", host))
+    let host = match host.parse::<IpAddr>() {
+        Ok(IpAddr::V6(ip)) => format!("[{}]", ip),
+        _ => host,
+    };
+
+    Ok(format!("http://{}:{}/upload/{}", host, server.addr.port(), token))
+}
+
+// One-time token for an upload URL: 16 bytes from the kernel's CSPRNG, hex encoded.
+fn new_upload_token() -> Result<String, DabError> {
+    let mut bytes = [0u8; 16];
+    File::open("/dev/urandom")
+        .and_then(|mut urandom| urandom.read_exact(&mut bytes))
+        .map_err(|e| DabError::Err500(format!("Failed to create an upload token: {}", e)))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+// Compares tokens in constant time, so that the time taken doesn't tell how much of a guess is
+// right.
+fn token_matches(expected: &str, token: &str) -> bool {
+    expected.len() == token.len()
+        && expected
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+// Largest width or height a screenshot is scaled to.
+const MAX_DIMENSION: u32 = 7680;
+const DEFAULT_JPEG_QUALITY: u8 = 85;
+
+#[derive(PartialEq)]
+enum ImageFormat {
+    Png,
+    Jpeg,
+    WebP,
+}
+
+impl ImageFormat {
+    fn mime_type(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "image/png",
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::WebP => "image/webp",
+        }
+    }
+}
+
+fn parse_format(format: &Option<String>) -> Result<ImageFormat, DabError> {
+    match format.as_deref().map(|f| f.to_lowercase()).as_deref() {
+        None | Some("png") => Ok(ImageFormat::Png),
+        Some("jpeg") | Some("jpg") => Ok(ImageFormat::Jpeg),
+        Some("webp") => Ok(ImageFormat::WebP),
+        Some(other) => Err(DabError::Err400(format!(
+            "Unsupported image format '{}'; expected png, jpeg or webp",
+            other
+        ))),
+    }
+}
+
+fn validate_options(request: &CaptureScreenshotRequest) -> Result<ImageFormat, DabError> {
+    let format = parse_format(&request.format)?;
+
+    if let Some(quality) = request.quality {
+        // PNG and WebP (the image crate only encodes lossless WebP) have no quality setting.
+        if format != ImageFormat::Jpeg {
+            return Err(DabError::Err400("'quality' is only supported with the jpeg format".to_string()));
+        }
+        if !(1..=100).contains(&quality) {
+            return Err(DabError::Err400("'quality' must be between 1 and 100".to_string()));
+        }
+    }
+
+    for (name, value) in [("width", request.width), ("height", request.height)] {
+        if let Some(value) = value {
+            if value == 0 || value > MAX_DIMENSION {
+                return Err(DabError::Err400(format!(
+                    "'{}' must be between 1 and {}",
+                    name, MAX_DIMENSION
+                )));
+            }
+        }
+    }
+
+    if let Some(crop) = &request.crop {
+        if crop.width == 0 || crop.height == 0 {
+            return Err(DabError::Err400("'crop' must have a non-zero width and height".to_string()));
+        }
+    }
+
+    Ok(format)
+}
+
+// Crops, scales and encodes the captured PNG as requested.
+fn process_image(
+    data: &[u8],
+    request: &CaptureScreenshotRequest,
+    format: &ImageFormat,
+) -> Result<Vec<u8>, DabError> {
+    let mut image = ::image::load_from_memory(data)
+        .map_err(|e| DabError::Err500(format!("Failed to decode the screenshot: {}", e)))?;
+
+    if let Some(crop) = &request.crop {
+        let fits_width = crop.x.checked_add(crop.width).is_some_and(|right| right <= image.width());
+        let fits_height = crop.y.checked_add(crop.height).is_some_and(|bottom| bottom <= image.height());
+        if !fits_width || !fits_height {
+            return Err(DabError::Err400(format!(
+                "'crop' is outside the {}x{} screen",
+                image.width(),
+                image.height()
+            )));
+        }
+        image = image.crop_imm(crop.x, crop.y, crop.width, crop.height);
+    }
+
+    let scale = |from: u32, to: u32, other: u32| -> u32 {
+        ((other as u64 * to as u64 + from as u64 / 2) / from as u64).clamp(1, MAX_DIMENSION as u64) as u32
+    };
+    let size = match (request.width, request.height) {
+        (Some(width), Some(height)) => Some((width, height)),
+        (Some(width), None) => Some((width, scale(image.width(), width, image.height()))),
+        (None, Some(height)) => Some((scale(image.height(), height, image.width()), height)),
+        (None, None) => None,
+    };
+    if let Some((width, height)) = size {
+        if (width, height) != (image.width(), image.height()) {
+            image = image.resize_exact(width, height, FilterType::Triangle);
+        }
+    }
+
+    let (image, output_format) = match format {
+        ImageFormat::Png => (image, ImageOutputFormat::Png),
+        ImageFormat::Jpeg => (
+            DynamicImage::ImageRgb8(image.to_rgb8()),
+            ImageOutputFormat::Jpeg(request.quality.unwrap_or(DEFAULT_JPEG_QUALITY)),
+        ),
+        ImageFormat::WebP => (DynamicImage::ImageRgba8(image.to_rgba8()), ImageOutputFormat::WebP),
+    };
+
+    let mut encoded = Cursor::new(Vec::new());
+    image
+        .write_to(&mut encoded, output_format)
+        .map_err(|e| DabError::Err500(format!("Failed to encode the screenshot: {}", e)))?;
+    Ok(encoded.into_inner())
 }
 
 #[allow(non_snake_case)]
 #[allow(dead_code)]
 #[allow(unused_mut)]
 pub fn process(_dab_request: CaptureScreenshotRequest) -> Result<String, DabError> {
+    let format = validate_options(&_dab_request)?;
+
     //######### Activate org.rdk.ScreenCapture #########
     if get_service_state("org.rdk.ScreenCapture")? != "activated" {
         service_activate("org.rdk.ScreenCapture".to_string())?;
@@ -103,8 +297,9 @@ pub fn process(_dab_request: CaptureScreenshotRequest) -> Result<String, DabErro
         callGUID: String,
     }
 
+    let token = new_upload_token()?;
     let req_params = UploadScreenCaptureRequestParams {
-        url: upload_url()?,
+        url: upload_url(upload_server, &token)?,
         callGUID: "12345".to_string(),
     };
 
@@ -116,12 +311,29 @@ pub fn process(_dab_request: CaptureScreenshotRequest) -> Result<String, DabErro
     };
 
     let json_string = serde_json::to_string(&request).unwrap();
-    http_post(json_string)?;
+    upload_server.expect_upload(Some(token));
+    if let Err(err) = http_post(json_string) {
+        upload_server.expect_upload(None);
+        return Err(err);
+    }
 
-    match upload_server.rx.recv_timeout(std::time::Duration::from_secs(30)) {
+    let upload = upload_server.rx.recv_timeout(std::time::Duration::from_secs(30));
+    upload_server.expect_upload(None);
+    match upload {
         Ok(data) => {
+            // The capture is a full resolution PNG; it is only decoded when something is asked of it.
+            let unchanged = format == ImageFormat::Png
+                && _dab_request.width.is_none()
+                && _dab_request.height.is_none()
+                && _dab_request.crop.is_none();
+            let data = if unchanged {
+                data.to_vec()
+            } else {
+                process_image(&data, &_dab_request, &format)?
+            };
+
             let b64 = general_purpose::STANDARD.encode(&data);
-            let b64 = format!("data:image/png;base64,{}", b64);
+            let b64 = format!("data:{};base64,{}", format.mime_type(), b64);
 
             ResponseOperator.outputImage = b64;
             Ok(serde_json::to_string(&ResponseOperator).unwrap())
@@ -138,9 +350,25 @@ pub fn process(_dab_request: CaptureScreenshotRequest) -> Result<String, DabErro
 async fn handle_req(
     req: Request<Body>,
     tx: Sender<Bytes>,
+    token: Arc<Mutex<Option<String>>>,
 ) -> Result<Response<Body>, Infallible> {
-    match (req.method(), req.uri().path()) {
-        (&Method::POST, "/upload") => {
+    match (req.method(), req.uri().path().strip_prefix("/upload/")) {
+        (&Method::POST, Some(upload_token)) => {
+            // Only the upload of the request in progress is taken, once.
+            let expected = {
+                let mut token = token.lock().unwrap();
+                if token.as_deref().is_some_and(|expected| token_matches(expected, upload_token)) {
+                    token.take()
+                } else {
+                    None
+                }
+            };
+            if expected.is_none() {
+                let mut forbidden = Response::new(Body::from("Unexpected upload"));
+                *forbidden.status_mut() = StatusCode::FORBIDDEN;
+                return Ok(forbidden);
+            }
+
             let whole_body = hyper::body::to_bytes(req.into_body()).await.unwrap();
 
             if tx.send(whole_body).is_err() {
@@ -155,3 +383,85 @@ async fn handle_req(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dab::structs::ScreenshotCrop;
+    use ::image::{GenericImageView, RgbImage};
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let image = RgbImage::from_fn(width, height, |x, y| ::image::Rgb([x as u8, y as u8, 128]));
+        let mut encoded = Cursor::new(Vec::new());
+        DynamicImage::ImageRgb8(image)
+            .write_to(&mut encoded, ImageOutputFormat::Png)
+            .unwrap();
+        encoded.into_inner()
+    }
+
+    fn request(format: Option<&str>, quality: Option<u8>) -> CaptureScreenshotRequest {
+        CaptureScreenshotRequest {
+            format: format.map(|f| f.to_string()),
+            quality,
+            ..Default::default()
+        }
+    }
+
+    fn processed_size(data: &[u8], request: &CaptureScreenshotRequest) -> (u32, u32) {
+        let processed = process_image(data, request, &ImageFormat::Png).unwrap();
+        ::image::load_from_memory(&processed).unwrap().dimensions()
+    }
+
+    #[test]
+    fn quality_is_only_accepted_with_jpeg() {
+        assert!(matches!(validate_options(&request(None, None)), Ok(ImageFormat::Png)));
+        assert!(matches!(validate_options(&request(Some("JPG"), Some(50))), Ok(ImageFormat::Jpeg)));
+        assert!(matches!(validate_options(&request(Some("png"), Some(50))), Err(DabError::Err400(_))));
+        assert!(matches!(validate_options(&request(Some("webp"), Some(50))), Err(DabError::Err400(_))));
+        assert!(matches!(validate_options(&request(None, Some(50))), Err(DabError::Err400(_))));
+        assert!(matches!(validate_options(&request(Some("jpeg"), Some(0))), Err(DabError::Err400(_))));
+        assert!(matches!(validate_options(&request(Some("gif"), None)), Err(DabError::Err400(_))));
+    }
+
+    #[test]
+    fn crop_must_fit_the_screen() {
+        let data = png(100, 50);
+        let crop = |x, y, width, height| CaptureScreenshotRequest {
+            crop: Some(ScreenshotCrop { x, y, width, height }),
+            ..Default::default()
+        };
+
+        assert_eq!(processed_size(&data, &crop(90, 40, 10, 10)), (10, 10));
+        for request in [crop(91, 0, 10, 10), crop(0, 41, 10, 10), crop(u32::MAX, 0, 10, 10)] {
+            assert!(matches!(process_image(&data, &request, &ImageFormat::Png), Err(DabError::Err400(_))));
+        }
+    }
+
+    #[test]
+    fn scaling_one_side_keeps_the_aspect_ratio() {
+        let data = png(100, 50);
+        let size = |width, height| CaptureScreenshotRequest { width, height, ..Default::default() };
+
+        // 50 * 33 / 100 = 16.5, rounded to 17.
+        assert_eq!(processed_size(&data, &size(Some(33), None)), (33, 17));
+        assert_eq!(processed_size(&data, &size(None, Some(1))), (2, 1));
+        assert_eq!(processed_size(&data, &size(Some(40), Some(40))), (40, 40));
+        // A side that would round to 0 is kept at 1.
+        assert_eq!(processed_size(&png(100, 1), &size(Some(10), None)), (10, 1));
+    }
+
+    #[test]
+    fn jpeg_round_trip() {
+        let request = CaptureScreenshotRequest {
+            format: Some("jpeg".to_string()),
+            quality: Some(50),
+            width: Some(50),
+            ..Default::default()
+        };
+        let format = validate_options(&request).unwrap();
+        let encoded = process_image(&png(100, 50), &request, &format).unwrap();
+
+        assert_eq!(::image::guess_format(&encoded).unwrap(), ::image::ImageFormat::Jpeg);
+        assert_eq!(::image::load_from_memory(&encoded).unwrap().dimensions(), (50, 25));
+    }
+}
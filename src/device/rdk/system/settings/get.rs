@@ -13,6 +13,7 @@ use crate::device::rdk::interface::get_thunder_property;
 use crate::device::rdk::interface::rdk_request;
 use crate::device::rdk::interface::rdk_request_with_params;
 use crate::device::rdk::interface::rdk_sound_mode_to_dab;
+use crate::device::rdk::interface::thunder_plugin_exists;
 use crate::device::rdk::interface::RdkResponse;
 use crate::hw_specific::interface::get_service_state;
 use crate::hw_specific::interface::service_activate;
@@ -39,6 +40,17 @@ fn get_rdk_video_input_source() -> VideoInputSource {
 }
 
 pub fn get_rdk_language() -> Result<String, DabError> {
+    // Images predating the UserSettings plugin keep the UI language in the System plugin.
+    if !thunder_plugin_exists("org.rdk.UserSettings") {
+        #[derive(Deserialize)]
+        struct GetUILanguage {
+            ui_language: String,
+        }
+
+        let rdkresponse: RdkResponse<GetUILanguage> = rdk_request("org.rdk.System.getUILanguage")?;
+        return Ok(rdkresponse.result.ui_language);
+    }
+
     let rdkresponse: RdkResponse<String> =
         rdk_request("org.rdk.UserSettings.getPresentationLanguage")?;
 
@@ -8,6 +8,7 @@ use crate::dab::structs::SetSystemSettingsRequest;
 use crate::dab::structs::VideoInputSource;
 use crate::device::rdk::interface::rdk_request_with_params;
 use crate::device::rdk::interface::service_is_available;
+use crate::device::rdk::interface::thunder_plugin_exists;
 use crate::device::rdk::interface::RdkResponseSimple;
 use crate::device::rdk::system::settings::get::set_current_video_input_source;
 
@@ -25,6 +26,22 @@ use serde_json::Value;
 use std::collections::HashMap;
 
 fn set_rdk_language(language: String) -> Result<(), DabError> {
+    // Images predating the UserSettings plugin keep the UI language in the System plugin.
+    if !thunder_plugin_exists("org.rdk.UserSettings") {
+        #[derive(Serialize)]
+        struct Param {
+            ui_language: String,
+        }
+
+        let _rdkresponse: RdkResponseSimple = rdk_request_with_params(
+            "org.rdk.System.setUILanguage",
+            Param {
+                ui_language: language,
+            },
+        )?;
+        return Ok(());
+    }
+
     #[derive(Serialize)]
     #[allow(non_snake_case)]
     struct Param {